@@ -31,6 +31,29 @@ export type RecentAction = {
   status: "success" | "pending" | "failed";
 };
 
+export type LaunchProfileReport = {
+  target: string;
+  profile: number;
+  exePath: string;
+  status: "launched" | "failed" | "cancelled" | "skipped";
+  pid: number | null;
+  deepLink: "not_requested" | "not_sent" | "sent" | "partial" | "failed";
+  error: string | null;
+  elapsedMs: number;
+};
+
+export type LaunchReport = {
+  startedAt: string;
+  finishedAt: string;
+  total: number;
+  launched: number;
+  failed: number;
+  skipped: number;
+  cancelled: boolean;
+  pids: number[];
+  profiles: LaunchProfileReport[];
+};
+
 export type DailyTaskItem = {
   id: string;
   title: string;
@@ -77,7 +100,7 @@ export const launchAccountsForProfiles = async (
   profileIds: number[],
   telegramFolderPath: string
 ) => {
-  return await invoke("launch_accounts_for_profiles", {
+  return await invoke<LaunchReport>("launch_accounts_for_profiles", {
     linkParams,
     profileIds,
     telegramFolderPath,
@@ -161,7 +184,7 @@ export const launchSingleChromeProfile = async (params: {
   profileName: string;
   targetUrl?: string | null;
 }) => {
-  return await invoke<boolean>("launch_single_chrome_profile", params);
+  return await invoke<LaunchReport>("launch_single_chrome_profile", params);
 };
 
 export const closeSingleChromeProfile = async (params: {
@@ -597,12 +597,12 @@ export default function Chrome() {
       const openedNames: string[] = [];
       for (const profileName of nextBatch) {
         try {
-          const launched = await launchSingleChromeProfile({
+          const report = await launchSingleChromeProfile({
             chromeFolderPath,
             profileName,
             targetUrl: selectedTargetUrl,
           });
-          if (launched) {
+          if (report.launched > 0) {
             opened += 1;
             openedNames.push(profileName);
           }
@@ -780,12 +780,12 @@ export default function Chrome() {
       const nextOpened: string[] = [];
       for (const profileName of nextBatch) {
         try {
-          const launched = await launchSingleChromeProfile({
+          const report = await launchSingleChromeProfile({
             chromeFolderPath,
             profileName,
             targetUrl: selectedTargetUrl,
           });
-          if (launched) nextOpened.push(profileName);
+          if (report.launched > 0) nextOpened.push(profileName);
         } catch (error) {
           console.warn(`Failed to open profile ${profileName}:`, error);
         }
@@ -931,11 +931,14 @@ export default function Chrome() {
           return;
         }
       } else {
-        await launchSingleChromeProfile({
+        const report = await launchSingleChromeProfile({
           chromeFolderPath,
           profileName: account.name,
           targetUrl: selectedTargetUrl,
         });
+        if (report.failed > 0) {
+          throw new Error(report.profiles[0]?.error ?? `Failed to open ${account.name}`);
+        }
       }
       setRunningProfiles((prev) => {
         const next = new Set(prev);
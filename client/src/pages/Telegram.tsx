@@ -1875,7 +1875,7 @@ export default function Telegram() {
       if (!params) {
         throw new Error("Missing launch params for project mode");
       }
-      pids = await launchAccountsForProfiles(params, profileIds, telegramFolderPath) as number[];
+      pids = (await launchAccountsForProfiles(params, profileIds, telegramFolderPath)).pids;
     }
     logLaunchReasonCodes(profileIds, "spawned", 1);
 
@@ -0,0 +1,311 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::layout::AccountLayout;
+use crate::store;
+
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+pub const ACCOUNT_STATUSES: [&str; 3] = ["active", "blocked", "inactive"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountKind {
+    Telegram,
+    Chrome,
+}
+
+impl AccountKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountKind::Telegram => "telegram",
+            AccountKind::Chrome => "chrome",
+        }
+    }
+
+    /// Chrome's own profile folder prefix; Telegram folders follow the workspace layout.
+    fn folder_prefix(&self) -> Option<&'static str> {
+        match self {
+            AccountKind::Telegram => None,
+            AccountKind::Chrome => Some("Profile "),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRecord {
+    pub id: String,
+    /// Workspace whose folders the record belongs to; empty for records from before workspaces.
+    #[serde(default)]
+    pub workspace_id: String,
+    #[serde(rename = "type")]
+    pub kind: AccountKind,
+    pub name: String,
+    pub folder: String,
+    #[serde(default = "default_status")]
+    pub status: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub project: String,
+    #[serde(default)]
+    pub last_active: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub present: bool,
+}
+
+fn default_status() -> String {
+    "active".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AccountRegistry {
+    #[serde(default)]
+    pub accounts: Vec<AccountRecord>,
+}
+
+pub fn account_key(kind: AccountKind, folder_name: &str) -> String {
+    format!("{}:{}", kind.as_str(), folder_name)
+}
+
+/// Registry id of a folder: the same folder name in two workspaces is two accounts.
+pub fn registry_key(workspace_id: &str, kind: AccountKind, folder_name: &str) -> String {
+    format!("{}:{}", workspace_id, account_key(kind, folder_name))
+}
+
+/// Accepts a registry id (`default:telegram:TG 5`), an account key (`telegram:TG 5`) or a
+/// bare Telegram number (`5`), which is how the webview has always addressed accounts.
+/// Keys and numbers are scoped to `workspace_id`; numbers are turned into folders through
+/// the workspace's `layout`.
+pub fn normalize_account_key(raw: &str, workspace_id: &str, layout: &AccountLayout) -> Result<String, String> {
+    let trimmed = raw.trim();
+    if trimmed.chars().all(|ch| ch.is_ascii_digit()) && !trimmed.is_empty() {
+        let folder = trimmed
+            .parse::<i32>()
+            .ok()
+            .and_then(|number| layout.folder_name(number))
+            .ok_or_else(|| format!("Account {} has no folder in the account layout", trimmed))?;
+        return Ok(registry_key(workspace_id, AccountKind::Telegram, &folder));
+    }
+    for kind in [AccountKind::Telegram, AccountKind::Chrome] {
+        if let Some(folder) = trimmed.strip_prefix(&account_key(kind, "")) {
+            return Ok(registry_key(workspace_id, kind, folder));
+        }
+    }
+    Ok(trimmed.to_string())
+}
+
+fn folder_number(kind: AccountKind, folder_name: &str) -> Option<u32> {
+    folder_name
+        .strip_prefix(kind.folder_prefix()?)
+        .and_then(|rest| rest.trim().parse::<u32>().ok())
+}
+
+/// Position of a record's folder: Telegram folders are numbered by the workspace `layout`,
+/// Chrome profiles by Chrome's own `Profile N` names.
+fn record_number(record: &AccountRecord, layout: &AccountLayout) -> Option<u32> {
+    match record.kind {
+        AccountKind::Telegram => layout
+            .account_number(&record.name)
+            .and_then(|number| u32::try_from(number).ok()),
+        AccountKind::Chrome => folder_number(AccountKind::Chrome, &record.name),
+    }
+}
+
+/// Orders records by kind, then folder number, then name; unnumbered folders go last.
+pub fn sort_records(records: &mut [AccountRecord], layout: &AccountLayout) {
+    records.sort_by_cached_key(|record| {
+        (
+            record.kind.as_str(),
+            record_number(record, layout).unwrap_or(u32::MAX),
+            record.name.clone(),
+        )
+    });
+}
+
+/// Lists `Profile N` folders directly under `root`, ordered by number.
+pub fn scan_account_folders(kind: AccountKind, root: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut found: Vec<(u32, String)> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            folder_number(kind, &name).map(|num| (num, name))
+        })
+        .collect();
+    found.sort_by_key(|(num, _)| *num);
+    found.into_iter().map(|(_, name)| name).collect()
+}
+
+impl AccountRegistry {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        store::load_json(path, "account registry")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        store::save_json(path, self, "account registry")
+    }
+
+    pub fn find_mut(&mut self, key: &str) -> Option<&mut AccountRecord> {
+        self.accounts.iter_mut().find(|account| account.id == key)
+    }
+
+    /// The workspace's records, ordered by its `layout`.
+    pub fn workspace_accounts(&self, workspace_id: &str, layout: &AccountLayout) -> Vec<AccountRecord> {
+        let mut records: Vec<AccountRecord> = self
+            .accounts
+            .iter()
+            .filter(|account| account.workspace_id == workspace_id)
+            .cloned()
+            .collect();
+        sort_records(&mut records, layout);
+        records
+    }
+
+    /// Adds records for newly found folders of one workspace and flags its records whose
+    /// folder is gone. Records are never dropped so notes and project assignments survive a
+    /// missing drive; unscoped records from before workspaces join the first workspace that
+    /// has their folder.
+    pub fn sync_folders(&mut self, workspace_id: &str, kind: AccountKind, root: &Path, folder_names: &[String]) {
+        for account in self
+            .accounts
+            .iter_mut()
+            .filter(|account| account.kind == kind && account.workspace_id == workspace_id)
+        {
+            account.present = folder_names.contains(&account.name);
+        }
+
+        for name in folder_names {
+            let key = registry_key(workspace_id, kind, name);
+            let folder = root.join(name).to_string_lossy().to_string();
+            if let Some(existing) = self.find_mut(&key) {
+                existing.folder = folder;
+                continue;
+            }
+            let legacy_key = account_key(kind, name);
+            if let Some(legacy) = self
+                .accounts
+                .iter_mut()
+                .find(|account| account.workspace_id.is_empty() && account.id == legacy_key)
+            {
+                legacy.id = key;
+                legacy.workspace_id = workspace_id.to_string();
+                legacy.folder = folder;
+                legacy.present = true;
+                continue;
+            }
+            self.accounts.push(AccountRecord {
+                id: key,
+                workspace_id: workspace_id.to_string(),
+                kind,
+                name: name.clone(),
+                folder,
+                status: default_status(),
+                notes: String::new(),
+                project: String::new(),
+                last_active: None,
+                present: true,
+            });
+        }
+    }
+}
+
+/// Runs a load/mutate/save cycle on the registry file under a process-wide lock.
+pub fn with_registry<T>(
+    path: &Path,
+    mutate: impl FnOnce(&mut AccountRegistry) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = REGISTRY_LOCK
+        .lock()
+        .map_err(|_| "Account registry lock is poisoned".to_string())?;
+    let mut registry = AccountRegistry::load(path)?;
+    let result = mutate(&mut registry)?;
+    registry.save(path)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_normalize_through_the_layout() {
+        let layout = AccountLayout {
+            folder_pattern: "acc_{n:02}".to_string(),
+            ..AccountLayout::default()
+        };
+        assert_eq!(normalize_account_key(" 7 ", "ws", &layout), Ok("ws:telegram:acc_07".to_string()));
+        assert_eq!(normalize_account_key("telegram:alice", "ws", &layout), Ok("ws:telegram:alice".to_string()));
+        assert_eq!(normalize_account_key("ws:chrome:Default", "ws", &layout), Ok("ws:chrome:Default".to_string()));
+        assert!(normalize_account_key("0", "ws", &layout).is_err());
+    }
+
+    fn record(kind: AccountKind, name: &str) -> AccountRecord {
+        AccountRecord {
+            id: account_key(kind, name),
+            workspace_id: String::new(),
+            kind,
+            name: name.to_string(),
+            folder: String::new(),
+            status: default_status(),
+            notes: String::new(),
+            project: String::new(),
+            last_active: None,
+            present: true,
+        }
+    }
+
+    #[test]
+    fn records_sort_by_the_layout_number() {
+        let layout = AccountLayout {
+            folder_pattern: "acc_{n:03}".to_string(),
+            ..AccountLayout::default()
+        };
+        let mut records = vec![
+            record(AccountKind::Telegram, "spare"),
+            record(AccountKind::Telegram, "acc_010"),
+            record(AccountKind::Chrome, "Profile 10"),
+            record(AccountKind::Telegram, "acc_002"),
+            record(AccountKind::Chrome, "Profile 2"),
+        ];
+        sort_records(&mut records, &layout);
+        let names: Vec<&str> = records.iter().map(|record| record.name.as_str()).collect();
+        assert_eq!(names, ["Profile 2", "Profile 10", "acc_002", "acc_010", "spare"]);
+    }
+
+    #[test]
+    fn sync_only_touches_the_workspace_being_synced() {
+        let root = Path::new("tg");
+        let mut registry = AccountRegistry {
+            accounts: vec![record(AccountKind::Telegram, "TG 1")],
+        };
+        registry.accounts[0].notes = "kept".to_string();
+
+        registry.sync_folders("a", AccountKind::Telegram, root, &["TG 1".to_string(), "TG 2".to_string()]);
+        registry.sync_folders("b", AccountKind::Telegram, root, &["TG 1".to_string()]);
+        registry.sync_folders("a", AccountKind::Telegram, root, &["TG 2".to_string()]);
+
+        let layout = AccountLayout::default();
+        let a = registry.workspace_accounts("a", &layout);
+        let ids: Vec<&str> = a.iter().map(|record| record.id.as_str()).collect();
+        assert_eq!(ids, ["a:telegram:TG 1", "a:telegram:TG 2"]);
+        assert_eq!(a[0].notes, "kept");
+        assert!(!a[0].present);
+        assert!(a[1].present);
+
+        let b = registry.workspace_accounts("b", &layout);
+        assert_eq!(b.len(), 1);
+        assert!(b[0].present);
+        assert!(b[0].notes.is_empty());
+    }
+}
@@ -0,0 +1,764 @@
+use serde::Serialize;
+use url::form_urlencoded;
+
+use crate::TelegramLink;
+
+/// Longest payload Telegram accepts for `start`, `startgroup` and `startchannel`.
+const MAX_START_PAYLOAD: usize = 64;
+/// Longest payload Telegram accepts for `startapp`.
+const MAX_STARTAPP_PAYLOAD: usize = 512;
+
+const TELEGRAM_HOSTS: [&str; 4] = ["t.me", "www.t.me", "telegram.me", "telegram.dog"];
+
+/// Rights accepted in the `admin=` parameter of `startgroup`/`startchannel` links.
+const ADMIN_RIGHTS: [&str; 15] = [
+    "change_info",
+    "post_messages",
+    "edit_messages",
+    "delete_messages",
+    "restrict_members",
+    "invite_users",
+    "pin_messages",
+    "manage_topics",
+    "promote_members",
+    "manage_video_chats",
+    "anonymous",
+    "manage_chat",
+    "post_stories",
+    "edit_stories",
+    "delete_stories",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// `tg://resolve?domain=` with optional mini app and `start`/`startapp` payload.
+    Resolve,
+    StartGroup,
+    StartChannel,
+    Join,
+    AddList,
+    MsgUrl,
+    Post,
+    /// `tg://privatepost` for a post in a private channel, addressed by numeric channel id.
+    PrivatePost,
+    VoiceChat,
+}
+
+impl LinkKind {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "resolve" => Ok(LinkKind::Resolve),
+            "startgroup" => Ok(LinkKind::StartGroup),
+            "startchannel" => Ok(LinkKind::StartChannel),
+            "join" => Ok(LinkKind::Join),
+            "addlist" => Ok(LinkKind::AddList),
+            "msg_url" => Ok(LinkKind::MsgUrl),
+            "post" => Ok(LinkKind::Post),
+            "privatepost" => Ok(LinkKind::PrivatePost),
+            "voicechat" => Ok(LinkKind::VoiceChat),
+            other => Err(format!("Unknown link kind: {}", other)),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinkKind::Resolve => "resolve",
+            LinkKind::StartGroup => "startgroup",
+            LinkKind::StartChannel => "startchannel",
+            LinkKind::Join => "join",
+            LinkKind::AddList => "addlist",
+            LinkKind::MsgUrl => "msg_url",
+            LinkKind::Post => "post",
+            LinkKind::PrivatePost => "privatepost",
+            LinkKind::VoiceChat => "voicechat",
+        }
+    }
+}
+
+fn encode(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Public usernames: 4-32 characters of `A-Z a-z 0-9 _`, starting with a letter.
+pub fn validate_username(raw: &str) -> Result<String, String> {
+    let name = raw.trim().trim_start_matches('@');
+    if name.is_empty() {
+        return Err("Bot username (app_name) is required".to_string());
+    }
+    let valid = (4..=32).contains(&name.len())
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!(
+            "Invalid username \"{}\": use 4-32 letters, digits or underscores, starting with a letter",
+            name
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Mini app short names: 3-30 characters of `A-Z a-z 0-9 _`.
+fn validate_app_short_name(raw: &str) -> Result<String, String> {
+    let name = raw.trim();
+    let valid = (3..=30).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!(
+            "Invalid mini app name \"{}\": use 3-30 letters, digits or underscores",
+            name
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Start parameters, invite hashes and folder slugs share the `A-Z a-z 0-9 _ -` alphabet.
+fn validate_token(value: &str, max_len: usize, what: &str) -> Result<String, String> {
+    if value.is_empty() {
+        return Err(format!("{} is required", what));
+    }
+    if value.len() > max_len {
+        return Err(format!("{} is longer than {} characters", what, max_len));
+    }
+    if let Some(bad) = value.chars().find(|c| !is_token_char(*c)) {
+        return Err(format!(
+            "{} contains '{}'; only letters, digits, '_' and '-' are allowed",
+            what, bad
+        ));
+    }
+    Ok(value.to_string())
+}
+
+fn validate_admin_rights(raw: &str) -> Result<Vec<String>, String> {
+    let mut rights = Vec::new();
+    for right in raw
+        .split(|c: char| c == ',' || c == '+' || c.is_whitespace())
+        .map(|right| right.trim().to_ascii_lowercase())
+        .filter(|right| !right.is_empty())
+    {
+        if !ADMIN_RIGHTS.contains(&right.as_str()) {
+            return Err(format!("Unknown admin right: {}", right));
+        }
+        if !rights.contains(&right) {
+            rights.push(right);
+        }
+    }
+    Ok(rights)
+}
+
+fn parse_url_like(raw: &str) -> Option<url::Url> {
+    let trimmed = raw.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    if lower.starts_with("tg://") || lower.starts_with("http://") || lower.starts_with("https://") {
+        return url::Url::parse(trimmed).ok();
+    }
+    if TELEGRAM_HOSTS.iter().any(|host| lower.starts_with(&format!("{}/", host))) {
+        return url::Url::parse(&format!("https://{}", trimmed)).ok();
+    }
+    None
+}
+
+fn is_telegram_host(url: &url::Url) -> bool {
+    url.host_str()
+        .map(|host| TELEGRAM_HOSTS.contains(&host.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Value of the first matching query key when `raw` is a URL or query string.
+fn find_param(raw: &str, keys: &[&str]) -> Option<String> {
+    let query = match parse_url_like(raw) {
+        Some(url) => url.query().unwrap_or_default().to_string(),
+        None if raw.contains('=') => raw.trim_start_matches('?').to_string(),
+        None => return None,
+    };
+    form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| keys.contains(&key.as_ref()))
+        .map(|(_, value)| value.to_string())
+}
+
+/// Takes the value of the first matching query key when `raw` is a URL or query string,
+/// otherwise returns `raw` itself.
+fn payload_from(raw: &str, keys: &[&str]) -> String {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return String::new();
+    }
+    if parse_url_like(raw).is_none() && !raw.contains('=') {
+        return raw.to_string();
+    }
+    find_param(raw, keys).unwrap_or_else(|| {
+        println!("[LOG] No {} parameter in ref_link, using it as is", keys.join("/"));
+        raw.to_string()
+    })
+}
+
+/// Accepts `hash`, `+hash`, `joinchat/hash`, `t.me/+hash` or `tg://join?invite=hash`.
+fn invite_hash(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(url) = parse_url_like(raw) {
+        if url.scheme() == "tg" {
+            return url
+                .query_pairs()
+                .find(|(key, _)| key == "invite")
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_default();
+        }
+        let path = url.path().trim_start_matches('/');
+        return path
+            .strip_prefix("joinchat/")
+            .unwrap_or(path)
+            .trim_start_matches('+')
+            .to_string();
+    }
+    raw.strip_prefix("joinchat/").unwrap_or(raw).trim_start_matches('+').to_string()
+}
+
+/// Accepts `slug`, `addlist/slug`, `t.me/addlist/slug` or `tg://addlist?slug=slug`.
+fn addlist_slug(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(url) = parse_url_like(raw) {
+        if url.scheme() == "tg" {
+            return url
+                .query_pairs()
+                .find(|(key, _)| key == "slug")
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_default();
+        }
+        let path = url.path().trim_start_matches('/');
+        return path.strip_prefix("addlist/").unwrap_or(path).to_string();
+    }
+    raw.strip_prefix("addlist/").unwrap_or(raw).to_string()
+}
+
+fn resolve_link(domain: &str, app_type: &str, ref_link: &str) -> Result<String, String> {
+    let mut link = format!("tg://resolve?domain={}", domain);
+    if !app_type.is_empty() {
+        link += &format!("&appname={}", encode(&validate_app_short_name(app_type)?));
+        let payload = payload_from(ref_link, &["startapp", "start"]);
+        if !payload.is_empty() {
+            let payload = validate_token(&payload, MAX_STARTAPP_PAYLOAD, "startapp payload")?;
+            link += &format!("&startapp={}", encode(&payload));
+        } else {
+            println!("[LOG] app_type provided without payload, using appname only");
+        }
+    } else if let Some(payload) = find_param(ref_link, &["startapp"]) {
+        // `?startapp=` without an app name opens the bot's main mini app.
+        if payload.is_empty() {
+            link += "&startapp";
+        } else {
+            let payload = validate_token(&payload, MAX_STARTAPP_PAYLOAD, "startapp payload")?;
+            link += &format!("&startapp={}", encode(&payload));
+        }
+        println!("[LOG] Using main mini app startapp without app_type");
+    } else if !ref_link.is_empty() {
+        let payload = payload_from(ref_link, &["start"]);
+        let payload = validate_token(&payload, MAX_START_PAYLOAD, "start payload")?;
+        link += &format!("&start={}", encode(&payload));
+        println!("[LOG] Using start without app_type");
+    } else {
+        println!("[LOG] No app_type/ref_link payload, using domain-only resolve link");
+    }
+    Ok(link)
+}
+
+fn add_bot_link(kind: LinkKind, domain: &str, ref_link: &str, admin_rights: &str) -> Result<String, String> {
+    let key = kind.as_str();
+    let mut link = format!("tg://resolve?domain={}&{}", domain, key);
+    let payload = payload_from(ref_link, &[key, "start"]);
+    if !payload.is_empty() {
+        let payload = validate_token(&payload, MAX_START_PAYLOAD, &format!("{} payload", key))?;
+        link += &format!("={}", encode(&payload));
+    }
+    let rights = validate_admin_rights(admin_rights)?;
+    if rights.is_empty() && kind == LinkKind::StartChannel {
+        return Err("startchannel links need at least one admin right".to_string());
+    }
+    if !rights.is_empty() {
+        link += &format!("&admin={}", rights.join("+"));
+    }
+    Ok(link)
+}
+
+/// Builds a `tg://` link for the kind selected in `link_kind`. A full `t.me`/`tg://` URL in
+/// `app_name` is parsed into fields first, so it goes through the same validation.
+pub fn build(params: &TelegramLink) -> Result<String, String> {
+    if parse_url_like(&params.app_name).is_some() {
+        return build(&parse(&params.app_name)?.link);
+    }
+
+    let app_type = params.app_type.trim();
+    let ref_link = params.ref_link.trim();
+    match LinkKind::parse(&params.link_kind)? {
+        LinkKind::Resolve => resolve_link(&validate_username(&params.app_name)?, app_type, ref_link),
+        kind @ (LinkKind::StartGroup | LinkKind::StartChannel) => add_bot_link(
+            kind,
+            &validate_username(&params.app_name)?,
+            ref_link,
+            &params.admin_rights,
+        ),
+        LinkKind::Join => {
+            let hash = validate_token(&invite_hash(ref_link), 64, "Invite hash")?;
+            Ok(format!("tg://join?invite={}", hash))
+        }
+        LinkKind::AddList => {
+            let slug = validate_token(&addlist_slug(ref_link), 64, "Folder slug")?;
+            Ok(format!("tg://addlist?slug={}", slug))
+        }
+        LinkKind::MsgUrl => {
+            if ref_link.is_empty() {
+                return Err("URL to share (ref_link) is required".to_string());
+            }
+            let mut link = format!("tg://msg_url?url={}", encode(ref_link));
+            let text = params.message_text.trim();
+            if !text.is_empty() {
+                link += &format!("&text={}", encode(text));
+            }
+            Ok(link)
+        }
+        LinkKind::Post => {
+            let domain = validate_username(&params.app_name)?;
+            let post = params.post_id.trim();
+            if post.parse::<u64>().map(|id| id == 0).unwrap_or(true) {
+                return Err(format!("Invalid post id: \"{}\"", post));
+            }
+            Ok(format!("tg://resolve?domain={}&post={}", domain, post))
+        }
+        LinkKind::PrivatePost => {
+            let channel = params.app_name.trim();
+            let post = params.post_id.trim();
+            if channel.parse::<u64>().map(|id| id == 0).unwrap_or(true) {
+                return Err(format!("Invalid private channel id: \"{}\"", channel));
+            }
+            if post.parse::<u64>().map(|id| id == 0).unwrap_or(true) {
+                return Err(format!("Invalid post id: \"{}\"", post));
+            }
+            Ok(format!("tg://privatepost?channel={}&post={}", channel, post))
+        }
+        LinkKind::VoiceChat => {
+            let domain = validate_username(&params.app_name)?;
+            let hash = payload_from(ref_link, &["voicechat", "videochat", "livestream"]);
+            if hash.is_empty() {
+                return Ok(format!("tg://resolve?domain={}&voicechat", domain));
+            }
+            let hash = validate_token(&hash, 64, "Voice chat invite hash")?;
+            Ok(format!("tg://resolve?domain={}&voicechat={}", domain, hash))
+        }
+    }
+}
+
+/// Parameters carried over from `t.me/<username>?...` links to `tg://resolve`.
+const RESOLVE_PASSTHROUGH: [&str; 9] = [
+    "start",
+    "startapp",
+    "startgroup",
+    "startchannel",
+    "admin",
+    "voicechat",
+    "videochat",
+    "livestream",
+    "text",
+];
+
+/// Validates one `tg://resolve` parameter and returns it in link form (`key` or `key=value`).
+fn resolve_param(key: &str, value: &str) -> Result<String, String> {
+    if value.is_empty() {
+        return Ok(key.to_string());
+    }
+    let value = match key {
+        "start" | "startgroup" | "startchannel" => {
+            encode(&validate_token(value, MAX_START_PAYLOAD, &format!("{} payload", key))?)
+        }
+        "startapp" => encode(&validate_token(value, MAX_STARTAPP_PAYLOAD, "startapp payload")?),
+        "voicechat" | "videochat" | "livestream" => encode(&validate_token(value, 64, "Voice chat invite hash")?),
+        "appname" => encode(&validate_app_short_name(value)?),
+        "admin" => validate_admin_rights(value)?.join("+"),
+        "post" => {
+            if value.parse::<u64>().map(|id| id == 0).unwrap_or(true) {
+                return Err(format!("Invalid post id: \"{}\"", value));
+            }
+            value.to_string()
+        }
+        _ => encode(value),
+    };
+    Ok(format!("{}={}", key, value))
+}
+
+/// `tg://resolve` for `domain` with the allowed, validated parameters of `query`.
+fn resolve_with_params(domain: &str, query: &[(String, String)], allowed: &[&str]) -> Result<String, String> {
+    let mut link = format!("tg://resolve?domain={}", validate_username(domain)?);
+    for (key, value) in query {
+        if allowed.contains(&key.as_str()) {
+            link += &format!("&{}", resolve_param(key, value)?);
+        }
+    }
+    Ok(link)
+}
+
+fn msg_url_link(shared: &str, text: Option<&str>) -> Result<String, String> {
+    if shared.is_empty() {
+        return Err("Share link has no url parameter".to_string());
+    }
+    let mut link = format!("tg://msg_url?url={}", encode(shared));
+    if let Some(text) = text.filter(|text| !text.is_empty()) {
+        link += &format!("&text={}", encode(text));
+    }
+    Ok(link)
+}
+
+/// Re-validates a `tg://` link field by field and returns its canonical form.
+fn convert_tg_url(url: &url::Url, raw: &str) -> Result<String, String> {
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let query_value = |key: &str| {
+        query
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    };
+
+    match url.host_str().unwrap_or_default() {
+        "" => Err(format!("Invalid tg:// link: {}", raw.trim())),
+        "resolve" => {
+            let allowed: Vec<&str> = RESOLVE_PASSTHROUGH.iter().copied().chain(["appname", "post"]).collect();
+            resolve_with_params(&query_value("domain"), &query, &allowed)
+        }
+        "join" => {
+            let hash = validate_token(&query_value("invite"), 64, "Invite hash")?;
+            Ok(format!("tg://join?invite={}", hash))
+        }
+        "addlist" => {
+            let slug = validate_token(&query_value("slug"), 64, "Folder slug")?;
+            Ok(format!("tg://addlist?slug={}", slug))
+        }
+        "msg_url" => msg_url_link(&query_value("url"), Some(&query_value("text"))),
+        "privatepost" => {
+            let (channel, post) = (query_value("channel"), query_value("post"));
+            if channel.parse::<u64>().is_err() || post.parse::<u64>().is_err() {
+                return Err(format!("Invalid private post link: {}", raw.trim()));
+            }
+            Ok(format!("tg://privatepost?channel={}&post={}", channel, post))
+        }
+        other => Err(format!("Unsupported tg:// link type: {}", other)),
+    }
+}
+
+/// Converts an `https://t.me/...` (or `telegram.me`) link to the equivalent `tg://` link.
+/// `tg://` links are validated the same way and returned in canonical form.
+pub fn convert_url(raw: &str) -> Result<String, String> {
+    let url = parse_url_like(raw).ok_or_else(|| format!("Not a Telegram link: {}", raw.trim()))?;
+    if url.scheme() == "tg" {
+        return convert_tg_url(&url, raw);
+    }
+    if !is_telegram_host(&url) {
+        return Err(format!("Not a Telegram link: {}", raw.trim()));
+    }
+
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let query_value = |key: &str| {
+        query
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.clone())
+    };
+
+    match segments.as_slice() {
+        [] => Err(format!("Telegram link has no target: {}", raw.trim())),
+        [first, ..] if first.starts_with('+') || *first == "joinchat" => {
+            let hash = validate_token(&invite_hash(raw), 64, "Invite hash")?;
+            Ok(format!("tg://join?invite={}", hash))
+        }
+        ["addlist", slug] => {
+            let slug = validate_token(slug, 64, "Folder slug")?;
+            Ok(format!("tg://addlist?slug={}", slug))
+        }
+        ["share", ..] => msg_url_link(&query_value("url").unwrap_or_default(), query_value("text").as_deref()),
+        ["c", channel, post] => {
+            if channel.parse::<u64>().is_err() || post.parse::<u64>().is_err() {
+                return Err(format!("Invalid private post link: {}", raw.trim()));
+            }
+            Ok(format!("tg://privatepost?channel={}&post={}", channel, post))
+        }
+        [username] => resolve_with_params(username, &query, &RESOLVE_PASSTHROUGH),
+        [username, second] => {
+            let domain = validate_username(username)?;
+            if second.parse::<u64>().map(|id| id > 0).unwrap_or(false) {
+                return Ok(format!("tg://resolve?domain={}&post={}", domain, second));
+            }
+            let mut link = format!("tg://resolve?domain={}&appname={}", domain, validate_app_short_name(second)?);
+            if let Some(payload) = query_value("startapp").filter(|payload| !payload.is_empty()) {
+                let payload = validate_token(&payload, MAX_STARTAPP_PAYLOAD, "startapp payload")?;
+                link += &format!("&startapp={}", encode(&payload));
+            }
+            Ok(link)
+        }
+        _ => Err(format!("Unsupported Telegram link: {}", raw.trim())),
+    }
+}
+
+/// A pasted link decoded into `TelegramLink` fields, plus the names of the fields taken from it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedTelegramLink {
+    pub link: TelegramLink,
+    pub inferred: Vec<String>,
+    /// The canonical `tg://` form of the input.
+    pub tg_link: String,
+}
+
+/// Parses any `t.me`, `telegram.me` or `tg://` link into the fields `build` consumes.
+pub fn parse(raw: &str) -> Result<ParsedTelegramLink, String> {
+    let tg_link = convert_url(raw)?;
+    let url = url::Url::parse(&tg_link).map_err(|e| format!("Failed to parse link {}: {}", tg_link, e))?;
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let param = |key: &str| {
+        query
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.clone())
+    };
+
+    fn set_kind(link: &mut TelegramLink, kind: LinkKind, inferred: &mut Vec<&'static str>) {
+        link.link_kind = kind.as_str().to_string();
+        inferred.push("link_kind");
+    }
+
+    let mut link = TelegramLink::default();
+    let mut inferred: Vec<&'static str> = Vec::new();
+
+    match url.host_str().unwrap_or_default() {
+        "resolve" => {
+            link.app_name = param("domain").ok_or_else(|| format!("Link has no domain: {}", raw.trim()))?;
+            inferred.push("app_name");
+            if let Some(post) = param("post") {
+                set_kind(&mut link, LinkKind::Post, &mut inferred);
+                link.post_id = post;
+                inferred.push("post_id");
+            } else if let Some((key, value)) = ["startgroup", "startchannel"]
+                .iter()
+                .find_map(|key| param(key).map(|value| (*key, value)))
+            {
+                let kind = LinkKind::parse(key)?;
+                set_kind(&mut link, kind, &mut inferred);
+                if !value.is_empty() {
+                    link.ref_link = value;
+                    inferred.push("ref_link");
+                }
+                if let Some(admin) = param("admin") {
+                    link.admin_rights = validate_admin_rights(&admin)?.join(",");
+                    inferred.push("admin_rights");
+                }
+            } else if let Some(value) = ["voicechat", "videochat", "livestream"]
+                .iter()
+                .find_map(|key| param(key))
+            {
+                set_kind(&mut link, LinkKind::VoiceChat, &mut inferred);
+                if !value.is_empty() {
+                    link.ref_link = value;
+                    inferred.push("ref_link");
+                }
+            } else {
+                if let Some(app) = param("appname") {
+                    link.app_type = app;
+                    inferred.push("app_type");
+                }
+                match (param("startapp"), param("start")) {
+                    (Some(payload), _) if !link.app_type.is_empty() => {
+                        link.ref_link = payload;
+                        inferred.push("ref_link");
+                    }
+                    // Main mini app: keep the key so `build` emits `startapp` again.
+                    (Some(payload), _) => {
+                        link.ref_link = format!("startapp={}", payload);
+                        inferred.push("ref_link");
+                    }
+                    (None, Some(payload)) if link.app_type.is_empty() => {
+                        link.ref_link = payload;
+                        inferred.push("ref_link");
+                    }
+                    _ => {}
+                }
+            }
+        }
+        "join" => {
+            set_kind(&mut link, LinkKind::Join, &mut inferred);
+            link.ref_link = param("invite").unwrap_or_default();
+            inferred.push("ref_link");
+        }
+        "addlist" => {
+            set_kind(&mut link, LinkKind::AddList, &mut inferred);
+            link.ref_link = param("slug").unwrap_or_default();
+            inferred.push("ref_link");
+        }
+        "privatepost" => {
+            set_kind(&mut link, LinkKind::PrivatePost, &mut inferred);
+            link.app_name = param("channel").unwrap_or_default();
+            link.post_id = param("post").unwrap_or_default();
+            inferred.push("app_name");
+            inferred.push("post_id");
+        }
+        "msg_url" => {
+            set_kind(&mut link, LinkKind::MsgUrl, &mut inferred);
+            link.ref_link = param("url").unwrap_or_default();
+            inferred.push("ref_link");
+            if let Some(text) = param("text") {
+                link.message_text = text;
+                inferred.push("message_text");
+            }
+        }
+        other => return Err(format!("Unsupported Telegram link type: {}", other)),
+    }
+
+    Ok(ParsedTelegramLink {
+        link,
+        inferred: inferred.into_iter().map(str::to_string).collect(),
+        tg_link,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(raw: &str) -> (TelegramLink, String) {
+        let parsed = parse(raw).unwrap_or_else(|e| panic!("parse {}: {}", raw, e));
+        let built = build(&parsed.link).unwrap_or_else(|e| panic!("build {}: {}", raw, e));
+        assert_eq!(built, parsed.tg_link, "round trip of {}", raw);
+        (parsed.link, built)
+    }
+
+    #[test]
+    fn mini_app_links_round_trip() {
+        let (link, built) = round_trip("https://t.me/rollsgame_bot/app?startapp=ref_xEmnaKUVPi");
+        assert_eq!(link.app_name, "rollsgame_bot");
+        assert_eq!(link.app_type, "app");
+        assert_eq!(link.ref_link, "ref_xEmnaKUVPi");
+        assert_eq!(built, "tg://resolve?domain=rollsgame_bot&appname=app&startapp=ref_xEmnaKUVPi");
+
+        let (link, _) = round_trip("telegram.me/xbot_bot/game");
+        assert_eq!(link.app_type, "game");
+        assert!(link.ref_link.is_empty());
+    }
+
+    #[test]
+    fn bot_start_links_round_trip() {
+        let (link, built) = round_trip("t.me/LootlyGameBot?start=abc-1");
+        assert_eq!(link.app_name, "LootlyGameBot");
+        assert!(link.app_type.is_empty());
+        assert_eq!(link.ref_link, "abc-1");
+        assert_eq!(built, "tg://resolve?domain=LootlyGameBot&start=abc-1");
+
+        let (link, built) = round_trip("https://t.me/virus_play_bot?startapp=main1");
+        assert!(link.app_type.is_empty());
+        assert_eq!(built, "tg://resolve?domain=virus_play_bot&startapp=main1");
+
+        // A bare `startapp` opens the main mini app without a payload.
+        let (link, built) = round_trip("https://t.me/virus_play_bot?startapp");
+        assert_eq!(link.ref_link, "startapp=");
+        assert_eq!(built, "tg://resolve?domain=virus_play_bot&startapp");
+
+        round_trip("tg://resolve?domain=getbonus");
+    }
+
+    #[test]
+    fn other_link_kinds_round_trip() {
+        let (link, _) = round_trip("https://t.me/my_group_bot?startgroup=x1&admin=change_info+pin_messages");
+        assert_eq!(link.link_kind, "startgroup");
+        assert_eq!(link.admin_rights, "change_info,pin_messages");
+
+        let (link, _) = round_trip("https://t.me/+AbCd-123");
+        assert_eq!(link.link_kind, "join");
+        assert_eq!(link.ref_link, "AbCd-123");
+
+        round_trip("https://t.me/joinchat/AbCd");
+        round_trip("t.me/addlist/slugX");
+        round_trip("https://t.me/durov/42");
+
+        let (link, built) = round_trip("https://t.me/c/123/45");
+        assert_eq!(link.link_kind, "privatepost");
+        assert_eq!(link.app_name, "123");
+        assert_eq!(link.post_id, "45");
+        assert_eq!(built, "tg://privatepost?channel=123&post=45");
+        round_trip("tg://resolve?domain=somechannel&voicechat=hash1");
+
+        let (link, _) = round_trip("https://t.me/share/url?url=https%3A%2F%2Fexample.com%2F%3Fa%3D1&text=hi%20there");
+        assert_eq!(link.ref_link, "https://example.com/?a=1");
+        assert_eq!(link.message_text, "hi there");
+    }
+
+    #[test]
+    fn built_links_parse_back_to_the_same_fields() {
+        let original = TelegramLink {
+            app_name: "Quantum_Machines_bot".to_string(),
+            app_type: "start".to_string(),
+            ref_link: "promo_7".to_string(),
+            ..TelegramLink::default()
+        };
+        let parsed = parse(&build(&original).unwrap()).unwrap();
+        assert_eq!(parsed.link.app_name, original.app_name);
+        assert_eq!(parsed.link.app_type, original.app_type);
+        assert_eq!(parsed.link.ref_link, original.ref_link);
+        assert_eq!(parsed.inferred, vec!["app_name", "app_type", "ref_link"]);
+    }
+
+    #[test]
+    fn rejects_foreign_and_malformed_links() {
+        assert!(parse("https://example.com/bot?start=x").is_err());
+        assert!(parse("https://t.me/").is_err());
+        assert!(parse("https://t.me/bot_name_bot?start=bad%20payload").is_err());
+    }
+
+    fn url_in_app_name(raw: &str) -> Result<String, String> {
+        build(&TelegramLink {
+            app_name: raw.to_string(),
+            ..TelegramLink::default()
+        })
+    }
+
+    #[test]
+    fn urls_in_app_name_are_validated() {
+        assert!(url_in_app_name("https://t.me/bot_name_bot?start=bad%20payload").is_err());
+        assert!(url_in_app_name("tg://resolve?domain=bot_name_bot&start=bad+payload").is_err());
+        assert!(url_in_app_name("tg://resolve?domain=x&start=ok").is_err());
+        assert!(url_in_app_name("tg://join?invite=a%2Fb").is_err());
+        assert_eq!(
+            url_in_app_name("https://t.me/bot_name_bot?start=good-1").unwrap(),
+            "tg://resolve?domain=bot_name_bot&start=good-1"
+        );
+        assert_eq!(
+            url_in_app_name("tg://resolve?domain=rollsgame_bot&appname=app&startapp=ref_1").unwrap(),
+            "tg://resolve?domain=rollsgame_bot&appname=app&startapp=ref_1"
+        );
+    }
+
+    #[test]
+    fn tg_links_are_checked_field_by_field() {
+        assert!(convert_url("tg://resolve?domain=bot_name_bot&start=bad+payload").is_err());
+        assert!(convert_url("tg://resolve?domain=bot_name_bot&admin=everything").is_err());
+        assert!(convert_url("tg://resolve?domain=durov&post=0").is_err());
+        assert!(convert_url("tg://addlist?slug=").is_err());
+        assert!(convert_url("tg://settings").is_err());
+        assert_eq!(
+            convert_url("tg://resolve?domain=my_group_bot&startgroup&admin=pin_messages+invite_users&utm=1").unwrap(),
+            "tg://resolve?domain=my_group_bot&startgroup&admin=pin_messages+invite_users"
+        );
+        assert_eq!(
+            convert_url("tg://msg_url?url=https%3A%2F%2Fexample.com&text=").unwrap(),
+            "tg://msg_url?url=https%3A%2F%2Fexample.com"
+        );
+    }
+}
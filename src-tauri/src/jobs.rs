@@ -0,0 +1,520 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::launcher::LaunchReport;
+use crate::store;
+use crate::{ChromeLaunchResult, TelegramLink};
+
+static JOBS_LOCK: Mutex<()> = Mutex::new(());
+
+const MAX_STORED_RUNS: usize = 500;
+/// A run missed by more than this (e.g. while the app was closed) is skipped, not fired late.
+const MISSED_RUN_GRACE_MINUTES: i64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum LaunchJobTarget {
+    #[serde(rename_all = "camelCase")]
+    Telegram {
+        link_params: Box<TelegramLink>,
+        start_range: i32,
+        end_range: i32,
+        /// Falls back to the configured Telegram folder when empty.
+        #[serde(default)]
+        telegram_folder_path: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Chrome {
+        #[serde(default)]
+        chrome_folder_path: String,
+        start_range: i32,
+        end_range: i32,
+        #[serde(default)]
+        mixed: bool,
+        #[serde(default)]
+        target_url: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JobSchedule {
+    At { at: DateTime<Utc> },
+    /// Five-field cron expression (`minute hour day-of-month month day-of-week`) in local time.
+    Cron { expression: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchJob {
+    pub id: String,
+    pub name: String,
+    /// Workspace whose folders and pacing the job uses. Empty on jobs saved before
+    /// workspaces existed; those follow the active workspace.
+    #[serde(default)]
+    pub workspace_id: String,
+    pub target: LaunchJobTarget,
+    pub schedule: JobSchedule,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub next_run_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchJobInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    /// Resolved by `save_launch_job`; `None` keeps the job's current workspace.
+    #[serde(skip)]
+    pub workspace_id: Option<String>,
+    pub target: LaunchJobTarget,
+    pub schedule: JobSchedule,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunOutcome {
+    Success,
+    Failed,
+    /// The scheduled time passed more than the grace period ago; the job was rescheduled.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRun {
+    pub id: String,
+    pub job_id: String,
+    pub job_name: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub outcome: JobRunOutcome,
+    #[serde(default)]
+    pub pids: Vec<u32>,
+    #[serde(default)]
+    pub chrome: Option<ChromeLaunchResult>,
+    #[serde(default)]
+    pub report: Option<LaunchReport>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct JobStore {
+    #[serde(default)]
+    pub jobs: Vec<LaunchJob>,
+    #[serde(default)]
+    pub runs: Vec<JobRun>,
+}
+
+#[derive(Debug, Clone)]
+struct CronField {
+    allowed: Vec<bool>,
+    min: u32,
+}
+
+impl CronField {
+    fn parse(raw: &str, min: u32, max: u32, name: &str) -> Result<Self, String> {
+        let mut allowed = vec![false; (max - min + 1) as usize];
+        for part in raw.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step = step
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|step| *step > 0)
+                        .ok_or_else(|| format!("Invalid step in cron {} field: {}", name, part))?;
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((a, b)) = range.split_once('-') {
+                let a = a.parse::<u32>().map_err(|_| format!("Invalid cron {} field: {}", name, part))?;
+                let b = b.parse::<u32>().map_err(|_| format!("Invalid cron {} field: {}", name, part))?;
+                (a, b)
+            } else {
+                let value = range
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid cron {} field: {}", name, part))?;
+                // "5/15" means "from 5 to the end of the range, every 15".
+                if step > 1 {
+                    (value, max)
+                } else {
+                    (value, value)
+                }
+            };
+
+            if start < min || end > max || start > end {
+                return Err(format!("Cron {} field out of range ({}-{}): {}", name, min, max, part));
+            }
+            let mut value = start;
+            while value <= end {
+                allowed[(value - min) as usize] = true;
+                value += step;
+            }
+        }
+        Ok(Self { allowed, min })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        value
+            .checked_sub(self.min)
+            .and_then(|index| self.allowed.get(index as usize))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    fn is_wildcard(&self) -> bool {
+        self.allowed.iter().all(|allowed| *allowed)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CronExpression {
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+}
+
+impl CronExpression {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Cron expression must have 5 fields (minute hour day month weekday): {}",
+                expression
+            ));
+        }
+        // Day-of-week accepts 0-7 where both 0 and 7 mean Sunday.
+        let mut day_of_week = CronField::parse(fields[4], 0, 7, "weekday")?;
+        if day_of_week.allowed[7] {
+            day_of_week.allowed[0] = true;
+        }
+        Ok(Self {
+            minute: CronField::parse(fields[0], 0, 59, "minute")?,
+            hour: CronField::parse(fields[1], 0, 23, "hour")?,
+            day_of_month: CronField::parse(fields[2], 1, 31, "day")?,
+            month: CronField::parse(fields[3], 1, 12, "month")?,
+            day_of_week,
+        })
+    }
+
+    fn day_matches(&self, time: &DateTime<Local>) -> bool {
+        let dom = self.day_of_month.matches(time.day());
+        let dow = self.day_of_week.matches(time.weekday().num_days_from_sunday());
+        // Classic cron: when both day fields are restricted, either may match.
+        match (self.day_of_month.is_wildcard(), self.day_of_week.is_wildcard()) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// First matching minute strictly after `after`, searching up to a year ahead.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut candidate = start;
+        let limit = start + Duration::days(366);
+
+        while candidate < limit {
+            if !self.month.matches(candidate.month()) || !self.day_matches(&candidate) {
+                let next_day = candidate.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                candidate = Local.from_local_datetime(&next_day).earliest()?;
+                continue;
+            }
+            if !self.hour.matches(candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minute.matches(candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            return Some(candidate);
+        }
+        None
+    }
+}
+
+impl JobSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            JobSchedule::At { .. } => Ok(()),
+            JobSchedule::Cron { expression } => CronExpression::parse(expression).map(|_| ()),
+        }
+    }
+
+    /// Next run strictly after `now`; one-shot jobs only run if still in the future.
+    pub fn next_run(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            JobSchedule::At { at } => (*at > now).then_some(*at),
+            JobSchedule::Cron { expression } => CronExpression::parse(expression)
+                .ok()?
+                .next_after(now.with_timezone(&Local))
+                .map(|next| next.with_timezone(&Utc)),
+        }
+    }
+}
+
+impl LaunchJob {
+    pub fn from_input(input: LaunchJobInput, now: DateTime<Utc>) -> Result<Self, String> {
+        let mut job = LaunchJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: String::new(),
+            workspace_id: String::new(),
+            target: input.target.clone(),
+            schedule: input.schedule.clone(),
+            enabled: true,
+            next_run_at: None,
+            last_run_at: None,
+            created_at: now,
+        };
+        job.apply_input(input, now)?;
+        Ok(job)
+    }
+
+    pub fn apply_input(&mut self, input: LaunchJobInput, now: DateTime<Utc>) -> Result<(), String> {
+        let name = input.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Job name is required".to_string());
+        }
+        input.schedule.validate()?;
+        let (start_range, end_range) = match &input.target {
+            LaunchJobTarget::Telegram { start_range, end_range, .. }
+            | LaunchJobTarget::Chrome { start_range, end_range, .. } => (*start_range, *end_range),
+        };
+        if start_range <= 0 || end_range < start_range {
+            return Err(format!("Invalid profile range: {}-{}", start_range, end_range));
+        }
+
+        self.name = name;
+        if let Some(workspace_id) = input.workspace_id {
+            self.workspace_id = workspace_id;
+        }
+        self.target = input.target;
+        self.schedule = input.schedule;
+        self.enabled = input.enabled;
+        self.next_run_at = self.schedule.next_run(now);
+        Ok(())
+    }
+}
+
+impl JobStore {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        store::load_json(path, "launch jobs")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        store::save_json(path, self, "launch jobs")
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut LaunchJob> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    /// Marks every due job as started and returns snapshots of them to run. Runs missed by more
+    /// than the grace period are recorded as skipped and only rescheduled.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<LaunchJob> {
+        let mut due = Vec::new();
+        let mut skipped = Vec::new();
+        for job in self.jobs.iter_mut().filter(|job| job.enabled) {
+            let Some(next_run_at) = job.next_run_at else {
+                continue;
+            };
+            if next_run_at > now {
+                continue;
+            }
+            job.next_run_at = job.schedule.next_run(now);
+            let late = now - next_run_at;
+            if late > Duration::minutes(MISSED_RUN_GRACE_MINUTES) {
+                skipped.push(JobRun {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    job_id: job.id.clone(),
+                    job_name: job.name.clone(),
+                    started_at: now,
+                    finished_at: now,
+                    outcome: JobRunOutcome::Skipped,
+                    pids: Vec::new(),
+                    chrome: None,
+                    report: None,
+                    error: Some(format!(
+                        "Missed run scheduled for {} ({} min late)",
+                        next_run_at.to_rfc3339(),
+                        late.num_minutes()
+                    )),
+                });
+                continue;
+            }
+            job.last_run_at = Some(now);
+            due.push(job.clone());
+        }
+        for run in skipped {
+            println!("[LOG] Skipping launch job {}: {}", run.job_id, run.error.as_deref().unwrap_or_default());
+            self.push_run(run);
+        }
+        due
+    }
+
+    pub fn push_run(&mut self, run: JobRun) {
+        self.runs.push(run);
+        if self.runs.len() > MAX_STORED_RUNS {
+            let overflow = self.runs.len() - MAX_STORED_RUNS;
+            self.runs.drain(..overflow);
+        }
+    }
+}
+
+/// Runs a load/mutate/save cycle on the job file under a process-wide lock.
+pub fn with_jobs<T>(
+    path: &Path,
+    mutate: impl FnOnce(&mut JobStore) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = JOBS_LOCK
+        .lock()
+        .map_err(|_| "Launch job lock is poisoned".to_string())?;
+    let mut jobs = JobStore::load(path)?;
+    let result = mutate(&mut jobs)?;
+    jobs.save(path)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).single().unwrap()
+    }
+
+    fn next(expression: &str, after: DateTime<Local>) -> DateTime<Local> {
+        CronExpression::parse(expression).unwrap().next_after(after).unwrap()
+    }
+
+    fn cron_job(id: &str, next_run_at: DateTime<Utc>) -> LaunchJob {
+        LaunchJob {
+            id: id.to_string(),
+            name: id.to_string(),
+            workspace_id: String::new(),
+            target: LaunchJobTarget::Chrome {
+                chrome_folder_path: String::new(),
+                start_range: 1,
+                end_range: 1,
+                mixed: false,
+                target_url: None,
+            },
+            schedule: JobSchedule::Cron { expression: "*/5 * * * *".to_string() },
+            enabled: true,
+            next_run_at: Some(next_run_at),
+            last_run_at: None,
+            created_at: next_run_at,
+        }
+    }
+
+    #[test]
+    fn parses_lists_ranges_and_steps() {
+        let field = CronField::parse("1,10-12,30/15", 0, 59, "minute").unwrap();
+        let matched: Vec<u32> = (0..60).filter(|value| field.matches(*value)).collect();
+        assert_eq!(matched, vec![1, 10, 11, 12, 30, 45]);
+
+        let field = CronField::parse("*/20", 0, 59, "minute").unwrap();
+        let matched: Vec<u32> = (0..60).filter(|value| field.matches(*value)).collect();
+        assert_eq!(matched, vec![0, 20, 40]);
+        assert!(CronField::parse("*", 1, 12, "month").unwrap().is_wildcard());
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expression in [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+        ] {
+            assert!(CronExpression::parse(expression).is_err(), "{} should be rejected", expression);
+        }
+    }
+
+    #[test]
+    fn weekday_seven_is_sunday() {
+        let expression = CronExpression::parse("0 9 * * 7").unwrap();
+        assert!(expression.day_of_week.matches(0));
+        // 2024-01-05 is a Friday; the next Sunday is the 7th.
+        assert_eq!(expression.next_after(local(2024, 1, 5, 12, 0)), Some(local(2024, 1, 7, 9, 0)));
+    }
+
+    #[test]
+    fn next_after_is_strictly_later() {
+        assert_eq!(next("30 9 * * *", local(2024, 1, 5, 9, 30)), local(2024, 1, 6, 9, 30));
+        assert_eq!(next("30 9 * * *", local(2024, 1, 5, 9, 29)), local(2024, 1, 5, 9, 30));
+        assert_eq!(next("*/15 * * * *", local(2024, 1, 5, 23, 50)), local(2024, 1, 6, 0, 0));
+        assert_eq!(next("0 0 1 * *", local(2024, 1, 5, 0, 0)), local(2024, 2, 1, 0, 0));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // 15th of the month or any Monday: from Friday 2024-01-05, Monday the 8th comes first.
+        assert_eq!(next("0 8 15 * 1", local(2024, 1, 5, 12, 0)), local(2024, 1, 8, 8, 0));
+        // With a wildcard weekday only the day of month counts.
+        assert_eq!(next("0 8 15 * *", local(2024, 1, 5, 12, 0)), local(2024, 1, 15, 8, 0));
+    }
+
+    #[test]
+    fn take_due_runs_recent_and_skips_stale_runs() {
+        let now = Utc::now();
+        let mut store = JobStore {
+            jobs: vec![
+                cron_job("recent", now - Duration::minutes(2)),
+                cron_job("stale", now - Duration::hours(3)),
+                cron_job("future", now + Duration::minutes(2)),
+            ],
+            runs: Vec::new(),
+        };
+
+        let due = store.take_due(now);
+        assert_eq!(due.iter().map(|job| job.id.as_str()).collect::<Vec<_>>(), vec!["recent"]);
+        assert_eq!(store.runs.len(), 1);
+        assert_eq!(store.runs[0].job_id, "stale");
+        assert_eq!(store.runs[0].outcome, JobRunOutcome::Skipped);
+
+        let stale = store.find_mut("stale").unwrap();
+        assert!(stale.last_run_at.is_none());
+        assert!(stale.next_run_at.unwrap() > now);
+    }
+
+    #[test]
+    fn stale_one_shot_job_is_not_rescheduled() {
+        let now = Utc::now();
+        let mut job = cron_job("once", now - Duration::days(1));
+        job.schedule = JobSchedule::At { at: now - Duration::days(1) };
+        let mut store = JobStore { jobs: vec![job], runs: Vec::new() };
+
+        assert!(store.take_due(now).is_empty());
+        assert_eq!(store.runs[0].outcome, JobRunOutcome::Skipped);
+        assert!(store.jobs[0].next_run_at.is_none());
+    }
+}
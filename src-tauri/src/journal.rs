@@ -0,0 +1,326 @@
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+const JOURNAL_FILE_NAME: &str = "actions.jsonl";
+const MAX_JOURNAL_BYTES: u64 = 512 * 1024;
+const MAX_ROTATED_FILES: usize = 5;
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    TelegramLaunch,
+    TelegramDeepLink,
+    TelegramClose,
+    TelegramCancel,
+    TelegramRestart,
+    ChromeLaunch,
+    ChromeClose,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionOutcome {
+    Success,
+    Failed,
+    Skipped,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionEvent {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub kind: ActionKind,
+    pub target: String,
+    pub outcome: ActionOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ActionEvent {
+    pub fn new(kind: ActionKind, target: &str, outcome: ActionOutcome, error: Option<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            kind,
+            target: target.to_string(),
+            outcome,
+            error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionQuery {
+    #[serde(default)]
+    pub kinds: Option<Vec<ActionKind>>,
+    /// Exact account or profile name (`TG 1` does not match `TG 10`), ignoring case.
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ActionQuery {
+    fn matches(&self, event: &ActionEvent, target_lower: Option<&str>) -> bool {
+        if let Some(kinds) = &self.kinds {
+            if !kinds.is_empty() && !kinds.contains(&event.kind) {
+                return false;
+            }
+        }
+        if let Some(target) = target_lower {
+            if event.target.trim().to_lowercase() != target {
+                return false;
+            }
+        }
+        if self.since.map(|since| event.timestamp < since).unwrap_or(false) {
+            return false;
+        }
+        if self.until.map(|until| event.timestamp > until).unwrap_or(false) {
+            return false;
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPage {
+    pub items: Vec<ActionEvent>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+fn journal_file(dir: &Path, generation: usize) -> PathBuf {
+    if generation == 0 {
+        dir.join(JOURNAL_FILE_NAME)
+    } else {
+        dir.join(format!("actions.{}.jsonl", generation))
+    }
+}
+
+fn rotate_if_needed(dir: &Path) -> Result<(), String> {
+    let current = journal_file(dir, 0);
+    let size = fs::metadata(&current).map(|meta| meta.len()).unwrap_or(0);
+    if size < MAX_JOURNAL_BYTES {
+        return Ok(());
+    }
+
+    let _ = fs::remove_file(journal_file(dir, MAX_ROTATED_FILES));
+    for generation in (0..MAX_ROTATED_FILES).rev() {
+        let from = journal_file(dir, generation);
+        if from.exists() {
+            fs::rename(&from, journal_file(dir, generation + 1))
+                .map_err(|e| format!("Failed to rotate action journal: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn append(dir: &Path, event: &ActionEvent) -> Result<(), String> {
+    let _guard = JOURNAL_LOCK
+        .lock()
+        .map_err(|_| "Action journal lock is poisoned".to_string())?;
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create journal directory: {}", e))?;
+    rotate_if_needed(dir)?;
+
+    let mut line = serde_json::to_string(event)
+        .map_err(|e| format!("Failed to serialize action event: {}", e))?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_file(dir, 0))
+        .map_err(|e| format!("Failed to open action journal: {}", e))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write action journal: {}", e))
+}
+
+/// Reads the journal newest-first across rotated files and returns one page of matches.
+pub fn query(dir: &Path, query: &ActionQuery) -> Result<ActionPage, String> {
+    let _guard = JOURNAL_LOCK
+        .lock()
+        .map_err(|_| "Action journal lock is poisoned".to_string())?;
+
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let target_lower = query
+        .target
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_lowercase);
+
+    let mut items: Vec<ActionEvent> = Vec::new();
+    let mut total = 0usize;
+
+    for generation in 0..=MAX_ROTATED_FILES {
+        let file = match fs::File::open(journal_file(dir, generation)) {
+            Ok(file) => file,
+            Err(_) => continue,
+        };
+
+        let mut events: Vec<ActionEvent> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<ActionEvent>(&line).ok())
+            .collect();
+        events.reverse();
+
+        for event in events {
+            if !query.matches(&event, target_lower.as_deref()) {
+                continue;
+            }
+            if total >= offset && items.len() < limit {
+                items.push(event);
+            }
+            total += 1;
+        }
+    }
+
+    Ok(ActionPage {
+        items,
+        total,
+        offset,
+        limit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("abuseapp-journal-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn event(target: &str, minute: u32) -> ActionEvent {
+        let mut event = ActionEvent::new(ActionKind::TelegramLaunch, target, ActionOutcome::Success, None);
+        event.timestamp = Utc.with_ymd_and_hms(2026, 10, 18, 12, minute, 0).unwrap();
+        event
+    }
+
+    /// Writes events oldest-first into one journal generation, as `append` would have.
+    fn write_generation(dir: &Path, generation: usize, events: &[ActionEvent]) {
+        let body: String = events
+            .iter()
+            .map(|event| serde_json::to_string(event).unwrap() + "\n")
+            .collect();
+        fs::write(journal_file(dir, generation), body).unwrap();
+    }
+
+    fn targets(page: &ActionPage) -> Vec<&str> {
+        page.items.iter().map(|event| event.target.as_str()).collect()
+    }
+
+    #[test]
+    fn append_rotates_full_files() {
+        let dir = temp_dir();
+        fs::write(journal_file(&dir, 0), vec![b'x'; MAX_JOURNAL_BYTES as usize]).unwrap();
+        fs::write(journal_file(&dir, MAX_ROTATED_FILES), "oldest").unwrap();
+
+        append(&dir, &event("TG 1", 0)).unwrap();
+
+        assert_eq!(fs::metadata(journal_file(&dir, 1)).unwrap().len(), MAX_JOURNAL_BYTES);
+        let current = fs::read_to_string(journal_file(&dir, 0)).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert!(!journal_file(&dir, MAX_ROTATED_FILES + 1).exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn query_pages_newest_first_across_rotated_files() {
+        let dir = temp_dir();
+        write_generation(&dir, 1, &[event("TG 1", 0), event("TG 2", 1)]);
+        write_generation(&dir, 0, &[event("TG 3", 2), event("TG 4", 3)]);
+
+        let all = query(&dir, &ActionQuery::default()).unwrap();
+        assert_eq!(targets(&all), ["TG 4", "TG 3", "TG 2", "TG 1"]);
+        assert_eq!(all.total, 4);
+
+        let page = query(
+            &dir,
+            &ActionQuery {
+                offset: Some(1),
+                limit: Some(2),
+                ..ActionQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(targets(&page), ["TG 3", "TG 2"]);
+        assert_eq!((page.total, page.offset, page.limit), (4, 1, 2));
+
+        let clamped = query(&dir, &ActionQuery { limit: Some(0), ..ActionQuery::default() }).unwrap();
+        assert_eq!(clamped.limit, 1);
+        assert_eq!(clamped.items.len(), 1);
+        let clamped = query(&dir, &ActionQuery { limit: Some(100_000), ..ActionQuery::default() }).unwrap();
+        assert_eq!(clamped.limit, MAX_PAGE_SIZE);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn query_filters_by_exact_target_and_time() {
+        let dir = temp_dir();
+        write_generation(&dir, 2, &[event("TG 1", 0), event("TG 10", 1)]);
+        write_generation(&dir, 0, &[event("tg 1", 5), event("TG 10", 6)]);
+
+        let by_target = query(
+            &dir,
+            &ActionQuery {
+                target: Some(" TG 1 ".to_string()),
+                ..ActionQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(by_target.total, 2);
+        assert!(by_target.items.iter().all(|event| event.target.eq_ignore_ascii_case("TG 1")));
+
+        let window = query(
+            &dir,
+            &ActionQuery {
+                since: Some(Utc.with_ymd_and_hms(2026, 10, 18, 12, 1, 0).unwrap()),
+                until: Some(Utc.with_ymd_and_hms(2026, 10, 18, 12, 5, 0).unwrap()),
+                ..ActionQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(targets(&window), ["tg 1", "TG 10"]);
+
+        let kinds = query(
+            &dir,
+            &ActionQuery {
+                kinds: Some(vec![ActionKind::TelegramClose]),
+                ..ActionQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(kinds.total, 0);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
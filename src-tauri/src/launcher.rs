@@ -0,0 +1,623 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::layout::AccountLayout;
+use crate::settings::TimingProfile;
+
+const CANCEL_POLL_MS: u64 = 100;
+const READINESS_POLL_MS: u64 = 500;
+/// Slack for filesystem timestamp granularity when comparing tdata mtimes to the spawn time.
+const TDATA_MTIME_SLACK_SECS: u64 = 2;
+
+/// Everything the engine needs to launch a set of `TG N` folders.
+#[derive(Debug, Clone)]
+pub struct LaunchPlan {
+    /// Launch order; outcomes are reported back in this order.
+    pub profiles: Vec<i32>,
+    pub telegram_folder_path: String,
+    pub layout: AccountLayout,
+    /// Folders for profiles that do not fit `layout` (e.g. `TG main`), keyed by the stand-in
+    /// profile id the caller gave them; every other profile resolves through the layout.
+    pub folders: HashMap<i32, String>,
+    /// Deep-link sequence per profile, each link sent through its own `Telegram.exe` spawn;
+    /// profiles without an entry launch bare.
+    pub links: HashMap<i32, Vec<PlannedLink>>,
+    /// Profiles an earlier run of a resumed session already spawned; they skip the bare spawn
+    /// and readiness check and only get their links.
+    pub already_spawned: HashSet<i32>,
+    pub start_in_tray: bool,
+    /// Spawn/link delays and jitter; the spawn delay is replaced by the readiness grace period
+    /// when `readiness` is set. `batch_cooldown_ms` applies after every `concurrency` profiles.
+    pub timing: TimingProfile,
+    /// Number of workers; each one paces itself with the timing above.
+    pub concurrency: usize,
+    pub readiness: Option<ReadinessCheck>,
+}
+
+impl LaunchPlan {
+    pub fn folder(&self, profile: i32) -> Option<String> {
+        self.folders
+            .get(&profile)
+            .cloned()
+            .or_else(|| self.layout.folder_name(profile))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedLink {
+    pub link: String,
+    /// Wait before sending this link; `None` uses the timing's link delay. The first link of a
+    /// sequence always follows the readiness check instead.
+    pub delay_ms: Option<u64>,
+}
+
+/// Gate for the deep link: the client must show up in the process list, stay alive for
+/// `grace_ms` and touch its `tdata` folder, all within `timeout_ms` of the bare spawn.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadinessCheck {
+    pub grace_ms: u64,
+    pub timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileStatus {
+    Launched,
+    Failed,
+    Cancelled,
+    /// Not started because it was already running (Chrome only).
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelStage {
+    BeforeSpawn,
+    BeforeDeepLink,
+    /// Between two links of a multi-step sequence.
+    BetweenLinkSteps,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepLinkStatus {
+    /// Bare launches, and Chrome profiles whose URL goes out with the spawn, never send one.
+    NotRequested,
+    /// The profile stopped before the deep-link spawn was attempted.
+    NotSent,
+    /// Every link of the sequence was sent.
+    Sent,
+    /// A multi-step sequence stopped after sending some of its links.
+    Partial,
+    Failed,
+}
+
+/// What happened to one profile during a Telegram or Chrome launch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileReport {
+    /// Folder name, e.g. `TG 5` or `Profile 3`.
+    pub target: String,
+    /// Number from the folder name; 0 for unnumbered Chrome profiles such as `Default`.
+    pub profile: i32,
+    pub exe_path: String,
+    pub status: ProfileStatus,
+    /// PID of the bare spawn.
+    pub pid: Option<u32>,
+    pub deep_link: DeepLinkStatus,
+    /// Links rendered for this profile in sequence order, whether or not they were sent.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    #[serde(default)]
+    pub steps_sent: usize,
+    /// Time from the bare spawn until the readiness check passed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_after_ms: Option<u64>,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_stage: Option<CancelStage>,
+}
+
+impl ProfileReport {
+    pub fn new(target: String, profile: i32, exe_path: &Path) -> Self {
+        Self {
+            target,
+            profile,
+            exe_path: exe_path.to_string_lossy().to_string(),
+            status: ProfileStatus::Launched,
+            pid: None,
+            deep_link: DeepLinkStatus::NotRequested,
+            links: Vec::new(),
+            steps_sent: 0,
+            ready_after_ms: None,
+            elapsed_ms: 0,
+            error: None,
+            cancel_stage: None,
+        }
+    }
+
+    pub fn failed(mut self, error: String) -> Self {
+        self.status = ProfileStatus::Failed;
+        self.error = Some(error);
+        self
+    }
+
+    fn cancelled(mut self, stage: CancelStage) -> Self {
+        self.status = ProfileStatus::Cancelled;
+        self.cancel_stage = Some(stage);
+        self
+    }
+}
+
+/// Kept snake_case: the webview already listens for this shape on `telegram-launch-progress`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchProgress {
+    pub batch_index: usize,
+    pub batch_total: usize,
+    pub profile: i32,
+}
+
+/// Per-profile results of one launch command, in launch order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub total: usize,
+    pub launched: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub cancelled: bool,
+    pub pids: Vec<u32>,
+    pub profiles: Vec<ProfileReport>,
+}
+
+impl LaunchReport {
+    pub fn from_profiles(started_at: DateTime<Utc>, total: usize, profiles: Vec<ProfileReport>) -> Self {
+        let mut report = Self {
+            started_at,
+            finished_at: Utc::now(),
+            total,
+            launched: 0,
+            failed: 0,
+            skipped: 0,
+            cancelled: false,
+            pids: Vec::new(),
+            profiles: Vec::new(),
+        };
+        for profile in profiles {
+            if let Some(pid) = profile.pid {
+                report.pids.push(pid);
+            }
+            match profile.status {
+                ProfileStatus::Launched => report.launched += 1,
+                ProfileStatus::Failed => report.failed += 1,
+                ProfileStatus::Skipped => report.skipped += 1,
+                ProfileStatus::Cancelled => report.cancelled = true,
+            }
+            report.profiles.push(profile);
+        }
+        report
+    }
+}
+
+pub enum LaunchEvent<'a> {
+    Progress(&'a LaunchProgress),
+    Outcome(&'a ProfileReport),
+    Complete(&'a LaunchReport),
+}
+
+pub type LaunchEventSink = Arc<dyn Fn(LaunchEvent<'_>) + Send + Sync>;
+
+struct EngineShared {
+    plan: LaunchPlan,
+    cancel: Option<Arc<AtomicBool>>,
+    sink: LaunchEventSink,
+    queue: Mutex<VecDeque<(usize, i32)>>,
+    /// Profiles after the latest batch boundary may not start before this instant.
+    batch_gate: Mutex<Option<tokio::time::Instant>>,
+    finished: AtomicUsize,
+    outcomes: Mutex<Vec<(usize, ProfileReport)>>,
+    /// Running Telegram exe paths and when they were listed, shared by every worker waiting
+    /// on readiness so the process list is read at most once per poll.
+    processes: tokio::sync::Mutex<Option<(Instant, Arc<Vec<String>>)>>,
+}
+
+impl EngineShared {
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .map(|flag| flag.load(Ordering::SeqCst))
+            .unwrap_or(false)
+    }
+
+    /// Sleeps for `ms`, waking early once the launch is cancelled so idle workers stop promptly.
+    async fn pause(&self, ms: u64) {
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_millis(ms);
+        while !self.is_cancelled() {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return;
+            }
+            let slice = (deadline - now).min(tokio::time::Duration::from_millis(CANCEL_POLL_MS));
+            tokio::time::sleep(slice).await;
+        }
+    }
+
+    /// Like [`pause`](Self::pause), plus the plan's random jitter.
+    async fn pace(&self, ms: u64) {
+        self.pause(self.plan.timing.jittered(ms)).await;
+    }
+
+    /// Normalized exe paths of the running Telegram clients, at most one poll interval old.
+    async fn running_paths(&self) -> Arc<Vec<String>> {
+        let mut snapshot = self.processes.lock().await;
+        if let Some((taken_at, paths)) = snapshot.as_ref() {
+            if taken_at.elapsed() < Duration::from_millis(READINESS_POLL_MS) {
+                return paths.clone();
+            }
+        }
+        let paths = Arc::new(
+            tokio::task::spawn_blocking(crate::running_telegram_paths)
+                .await
+                .unwrap_or_default(),
+        );
+        *snapshot = Some((Instant::now(), paths.clone()));
+        paths
+    }
+
+    fn blank_report(&self, profile: i32) -> ProfileReport {
+        let folder = self.plan.folder(profile);
+        let exe_path = folder
+            .as_ref()
+            .map(|folder| {
+                Path::new(&self.plan.telegram_folder_path)
+                    .join(folder)
+                    .join(self.plan.layout.exe_name.trim())
+            })
+            .unwrap_or_default();
+        let target = folder.unwrap_or_else(|| format!("Account {}", profile));
+        let mut report = ProfileReport::new(target, profile, &exe_path);
+        if let Some(links) = self.plan.links.get(&profile).filter(|links| !links.is_empty()) {
+            report.deep_link = DeepLinkStatus::NotSent;
+            report.links = links.iter().map(|step| step.link.clone()).collect();
+        }
+        report
+    }
+
+    /// Pops the next profile together with the time it may start. Taking the first profile
+    /// of a new batch moves that time forward by the batch cooldown.
+    fn next_profile(&self) -> Option<(usize, i32, Option<tokio::time::Instant>)> {
+        let mut queue = self.queue.lock().ok()?;
+        let mut gate = self.batch_gate.lock().ok()?;
+        let (position, profile) = queue.pop_front()?;
+        if let Some(cooldown_ms) = self.plan.timing.cooldown_before(position, self.plan.concurrency) {
+            *gate = Some(tokio::time::Instant::now() + tokio::time::Duration::from_millis(cooldown_ms));
+        }
+        Some((position, profile, *gate))
+    }
+
+    /// Reports every profile still queued as cancelled, so the report covers the whole plan.
+    fn drain_cancelled(&self) {
+        if !self.is_cancelled() {
+            return;
+        }
+        let remaining: Vec<(usize, i32)> = self
+            .queue
+            .lock()
+            .map(|mut queue| queue.drain(..).collect())
+            .unwrap_or_default();
+        for (position, profile) in remaining {
+            self.record(position, self.blank_report(profile).cancelled(CancelStage::BeforeSpawn));
+        }
+    }
+
+    fn record(&self, position: usize, outcome: ProfileReport) {
+        (self.sink)(LaunchEvent::Outcome(&outcome));
+        let cancelled = outcome.status == ProfileStatus::Cancelled;
+        let profile = outcome.profile;
+        if let Ok(mut outcomes) = self.outcomes.lock() {
+            outcomes.push((position, outcome));
+        }
+        if cancelled {
+            return;
+        }
+        let finished = self.finished.fetch_add(1, Ordering::SeqCst) + 1;
+        (self.sink)(LaunchEvent::Progress(&LaunchProgress {
+            batch_index: finished,
+            batch_total: self.plan.profiles.len(),
+            profile,
+        }));
+    }
+}
+
+async fn launch_profile(shared: &EngineShared, report: ProfileReport) -> ProfileReport {
+    let started = std::time::Instant::now();
+    let mut report = spawn_telegram(shared, report).await;
+    report.elapsed_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+async fn spawn_telegram(shared: &EngineShared, mut report: ProfileReport) -> ProfileReport {
+    let plan = &shared.plan;
+    let profile = report.profile;
+    let target = report.target.clone();
+    if plan.folder(profile).is_none() {
+        return report.failed(format!("Account {} has no folder in the account layout", profile));
+    }
+    let exe_path = PathBuf::from(&report.exe_path);
+    if !exe_path.exists() {
+        println!("File not found: {}", exe_path.display());
+        return report.failed(format!("{} not found: {}", plan.layout.exe_name.trim(), exe_path.display()));
+    }
+
+    let tray_args: Vec<&str> = if plan.start_in_tray { vec!["-startintray"] } else { vec![] };
+    if plan.already_spawned.contains(&profile) {
+        let Some(links) = plan.links.get(&profile).filter(|links| !links.is_empty()) else {
+            println!("[LOG] {} was already spawned, nothing left to send", target);
+            return report;
+        };
+        if shared.is_cancelled() {
+            println!("[LOG] Launch cancelled before deep link for {}", target);
+            return report.cancelled(CancelStage::BeforeDeepLink);
+        }
+        println!("[LOG] {} was already spawned, sending its links only", target);
+        return send_links(shared, report, &exe_path, &tray_args, links).await;
+    }
+
+    let spawned_at = SystemTime::now();
+    let mut child = match Command::new(&exe_path).args(&tray_args).spawn() {
+        Ok(child) => {
+            println!("[LOG] {} launched without params", target);
+            report.pid = Some(child.id());
+            child
+        }
+        Err(e) => {
+            println!("[LOG] Launch error {}: {}", exe_path.display(), e);
+            return report.failed(format!("Failed to launch {}: {}", target, e));
+        }
+    };
+
+    let Some(links) = plan.links.get(&profile).filter(|links| !links.is_empty()) else {
+        // Bare launches still hold the worker for the spawn delay before its next profile.
+        shared.pace(plan.timing.spawn_delay_ms).await;
+        return report;
+    };
+
+    let readiness = match plan.readiness {
+        Some(check) => {
+            let check = ReadinessCheck {
+                grace_ms: plan.timing.jittered(check.grace_ms),
+                ..check
+            };
+            Some(wait_until_ready(shared, &mut child, &exe_path, spawned_at, check).await)
+        }
+        None => {
+            shared.pace(plan.timing.spawn_delay_ms).await;
+            None
+        }
+    };
+    if shared.is_cancelled() {
+        println!("[LOG] Launch cancelled before deep link for {}", target);
+        return report.cancelled(CancelStage::BeforeDeepLink);
+    }
+    match readiness {
+        Some(Ok(ready_after_ms)) => {
+            println!("[LOG] {} ready after {} ms", target, ready_after_ms);
+            report.ready_after_ms = Some(ready_after_ms);
+        }
+        Some(Err(e)) => {
+            println!("[LOG] {}", e);
+            return report.failed(e);
+        }
+        None => {}
+    }
+    send_links(shared, report, &exe_path, &tray_args, links).await
+}
+
+/// Sends the link sequence to a running client, one `Telegram.exe` spawn per link.
+async fn send_links(
+    shared: &EngineShared,
+    mut report: ProfileReport,
+    exe_path: &Path,
+    tray_args: &[&str],
+    links: &[PlannedLink],
+) -> ProfileReport {
+    let plan = &shared.plan;
+    let target = report.target.clone();
+    for (step, planned) in links.iter().enumerate() {
+        if step > 0 {
+            shared.pace(planned.delay_ms.unwrap_or(plan.timing.link_delay_ms)).await;
+            if shared.is_cancelled() {
+                println!("[LOG] Launch cancelled after link step {} for {}", step, target);
+                report.deep_link = DeepLinkStatus::Partial;
+                return report.cancelled(CancelStage::BetweenLinkSteps);
+            }
+        }
+
+        let mut link_args = vec![planned.link.as_str()];
+        link_args.extend(tray_args.iter().copied());
+        if let Err(e) = Command::new(exe_path).args(link_args).spawn() {
+            println!("[LOG] Launch with params failed for {}: {}", target, e);
+            report.deep_link = if step == 0 { DeepLinkStatus::Failed } else { DeepLinkStatus::Partial };
+            let error = if links.len() == 1 {
+                format!("Failed to send deep link to {}: {}", target, e)
+            } else {
+                format!("Failed to send link step {} to {}: {}", step + 1, target, e)
+            };
+            return report.failed(error);
+        }
+        println!("{} launched with params {}.", target, planned.link);
+        report.steps_sent = step + 1;
+    }
+    report.deep_link = DeepLinkStatus::Sent;
+    shared.pace(plan.timing.link_delay_ms).await;
+    report
+}
+
+/// Telegram keeps `tdata/working` open while running and rewrites its settings and map
+/// files on start, so either counts as the client having picked up its profile.
+fn tdata_active(tdata: &Path, since: SystemTime) -> bool {
+    if tdata.join("working").exists() {
+        return true;
+    }
+    let since = since
+        .checked_sub(Duration::from_secs(TDATA_MTIME_SLACK_SECS))
+        .unwrap_or(since);
+    fs::read_dir(tdata)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+                .any(|modified| modified >= since)
+        })
+        .unwrap_or(false)
+}
+
+/// Polls until the freshly spawned client is ready for a deep link and returns how long
+/// that took. A second `Telegram.exe` for an already running folder hands off and exits,
+/// so the check follows processes running from the account folder, not just `child`.
+async fn wait_until_ready(
+    shared: &EngineShared,
+    child: &mut Child,
+    exe_path: &Path,
+    spawned_at: SystemTime,
+    check: ReadinessCheck,
+) -> Result<u64, String> {
+    let started = Instant::now();
+    let folder = exe_path.parent().unwrap_or(exe_path);
+    let folder_name = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let account_dir = crate::normalize_path_for_match(&folder.to_string_lossy());
+    let tdata = folder.join("tdata");
+    let grace = Duration::from_millis(check.grace_ms);
+    let timeout = Duration::from_millis(check.timeout_ms);
+    let mut alive_since: Option<Instant> = None;
+
+    loop {
+        if shared.is_cancelled() {
+            return Err(format!("{} launch cancelled", folder_name));
+        }
+
+        let running = shared
+            .running_paths()
+            .await
+            .iter()
+            .any(|path| crate::path_in_account_dir(path, &account_dir));
+        let exited = child.try_wait().ok().flatten();
+        if running {
+            alive_since.get_or_insert_with(Instant::now);
+        } else if let Some(status) = exited {
+            return Err(format!("{} exited before becoming ready ({})", folder_name, status));
+        }
+
+        if let Some(since) = alive_since {
+            if running && since.elapsed() >= grace && tdata_active(&tdata, spawned_at) {
+                return Ok(started.elapsed().as_millis() as u64);
+            }
+        }
+
+        if started.elapsed() >= timeout {
+            let reason = match alive_since {
+                None => "process never appeared",
+                Some(_) if !running => "process stopped running",
+                Some(_) => "no tdata activity",
+            };
+            return Err(format!(
+                "{} not ready after {}s: {}",
+                folder_name,
+                timeout.as_secs(),
+                reason
+            ));
+        }
+        shared.pause(READINESS_POLL_MS).await;
+    }
+}
+
+/// Pulls profiles off the shared queue until it is empty or the launch is cancelled.
+/// Each worker launches one profile at a time, so `concurrency` workers keep at most
+/// that many Telegram instances in their spawn/deep-link window.
+async fn run_worker(shared: Arc<EngineShared>) {
+    while let Some((position, profile, not_before)) = shared.next_profile() {
+        let report = shared.blank_report(profile);
+        if let Some(wait) = not_before.map(|at| at.saturating_duration_since(tokio::time::Instant::now())) {
+            if !wait.is_zero() {
+                println!("[LOG] Batch cooldown: waiting {} ms before {}", wait.as_millis(), report.target);
+                shared.pause(wait.as_millis() as u64).await;
+            }
+        }
+        if shared.is_cancelled() {
+            println!("[LOG] Launch cancelled before {}", report.target);
+            shared.record(position, report.cancelled(CancelStage::BeforeSpawn));
+            break;
+        }
+        println!("[LOG] Launching {}", report.target);
+        let outcome = launch_profile(&shared, report).await;
+        shared.record(position, outcome);
+    }
+    shared.drain_cancelled();
+}
+
+/// Runs a [`LaunchPlan`] and reports every step through one event sink, so the batch,
+/// profile-list and single-account commands share the same spawn/deep-link behavior.
+pub struct LaunchEngine {
+    shared: Arc<EngineShared>,
+}
+
+impl LaunchEngine {
+    /// `cancel` is polled before every spawn and deep link; `None` makes the run uncancellable.
+    pub fn new(plan: LaunchPlan, cancel: Option<Arc<AtomicBool>>, sink: LaunchEventSink) -> Self {
+        let queue = plan.profiles.iter().copied().enumerate().collect();
+        Self {
+            shared: Arc::new(EngineShared {
+                plan,
+                cancel,
+                sink,
+                queue: Mutex::new(queue),
+                batch_gate: Mutex::new(None),
+                finished: AtomicUsize::new(0),
+                outcomes: Mutex::new(Vec::new()),
+                processes: tokio::sync::Mutex::new(None),
+            }),
+        }
+    }
+
+    pub async fn run(self) -> LaunchReport {
+        let started_at = Utc::now();
+        let shared = self.shared;
+        let total = shared.plan.profiles.len();
+        let workers = shared.plan.concurrency.clamp(1, total.max(1));
+
+        let handles: Vec<_> = (0..workers)
+            .map(|_| tokio::spawn(run_worker(shared.clone())))
+            .collect();
+        for handle in handles {
+            if let Err(e) = handle.await {
+                println!("[LOG] Launch worker stopped unexpectedly: {}", e);
+            }
+        }
+
+        let mut outcomes = shared
+            .outcomes
+            .lock()
+            .map(|mut outcomes| std::mem::take(&mut *outcomes))
+            .unwrap_or_default();
+        outcomes.sort_by_key(|(position, _)| *position);
+        let profiles = outcomes.into_iter().map(|(_, outcome)| outcome).collect();
+
+        let report = LaunchReport::from_profiles(started_at, total, profiles);
+        (shared.sink)(LaunchEvent::Complete(&report));
+        report
+    }
+}
@@ -0,0 +1,370 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::accounts::{account_key, AccountKind};
+
+pub const DEFAULT_FOLDER_PATTERN: &str = "TG {n}";
+pub const DEFAULT_EXE_NAME: &str = "Telegram.exe";
+const MAX_PAD_WIDTH: usize = 9;
+
+/// How account folders are named under a Telegram root and which executable they hold.
+/// Launch, close and stats all resolve accounts through this, so they agree on the set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AccountLayout {
+    /// Folder name with one `{n}` placeholder, optionally zero-padded as `{n:03}`.
+    pub folder_pattern: String,
+    /// Explicit folder names, account N being the N-th entry; replaces the pattern when set.
+    pub folder_names: Vec<String>,
+    pub exe_name: String,
+}
+
+impl Default for AccountLayout {
+    fn default() -> Self {
+        Self {
+            folder_pattern: DEFAULT_FOLDER_PATTERN.to_string(),
+            folder_names: Vec::new(),
+            exe_name: DEFAULT_EXE_NAME.to_string(),
+        }
+    }
+}
+
+/// One account folder found on disk.
+#[derive(Debug, Clone)]
+pub struct AccountFolder {
+    /// Number in the folder layout; `None` for folders such as `TG main` that do not fit it.
+    pub number: Option<i32>,
+    pub name: String,
+    pub path: PathBuf,
+    pub has_exe: bool,
+    pub has_tdata: bool,
+}
+
+impl AccountFolder {
+    /// Order used by [`AccountLayout::scan`]: layout accounts by number, then the rest by name.
+    pub fn sort_key(&self) -> (bool, Option<i32>, String) {
+        (self.number.is_none(), self.number, self.name.to_lowercase())
+    }
+}
+
+/// Everything discovery knows about one folder under the Telegram root.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountIdentity {
+    /// Same key as the account registry (`telegram:<folder>`), stable across scans.
+    pub id: String,
+    pub folder: String,
+    pub number: Option<i32>,
+    pub exe_path: PathBuf,
+    pub has_exe: bool,
+    pub has_tdata: bool,
+    pub size_bytes: u64,
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+/// How commands address a Telegram account: a layout number (what the webview has always
+/// sent) or a discovery id / folder name.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum AccountRef {
+    Number(i32),
+    Key(String),
+}
+
+/// An [`AccountRef`] checked against the Telegram root.
+#[derive(Debug, Clone)]
+pub struct ResolvedAccount {
+    pub folder: String,
+    pub number: Option<i32>,
+}
+
+/// Total size of the files below `path`; symlinks are not followed.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(ft) if ft.is_dir() => dir_size(&entry.path()),
+            Ok(ft) if ft.is_file() => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+pub fn modified(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).ok()?.modified().ok().map(DateTime::<Utc>::from)
+}
+
+/// `prefix{n:0W}suffix` split around its placeholder.
+struct Pattern<'a> {
+    prefix: &'a str,
+    width: usize,
+    suffix: &'a str,
+}
+
+fn split_pattern(pattern: &str) -> Result<Pattern<'_>, String> {
+    let open = pattern
+        .find("{n")
+        .ok_or_else(|| format!("Folder pattern \"{}\" needs a {{n}} placeholder", pattern))?;
+    let close = pattern[open..]
+        .find('}')
+        .map(|offset| open + offset)
+        .ok_or_else(|| format!("Unclosed placeholder in folder pattern \"{}\"", pattern))?;
+    let width = match &pattern[open + 2..close] {
+        "" => 0,
+        spec => spec
+            .strip_prefix(":0")
+            .and_then(|width| width.parse::<usize>().ok())
+            .filter(|width| (1..=MAX_PAD_WIDTH).contains(width))
+            .ok_or_else(|| {
+                format!(
+                    "Folder pattern \"{}\": use {{n}} or {{n:0W}} with W up to {}",
+                    pattern, MAX_PAD_WIDTH
+                )
+            })?,
+    };
+    let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+    if prefix.contains(['{', '}']) || suffix.contains(['{', '}']) {
+        return Err(format!("Folder pattern \"{}\" must have exactly one placeholder", pattern));
+    }
+    Ok(Pattern { prefix, width, suffix })
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &value[prefix.len()..])
+}
+
+fn strip_suffix_ignore_case<'a>(value: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = value.len().checked_sub(suffix.len())?;
+    let tail = value.get(split..)?;
+    tail.eq_ignore_ascii_case(suffix).then(|| &value[..split])
+}
+
+fn check_file_name(name: &str, what: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(format!("Invalid {}: \"{}\"", what, name));
+    }
+    Ok(())
+}
+
+impl AccountLayout {
+    pub fn validate(&self) -> Result<(), String> {
+        check_file_name(&self.exe_name, "executable name")?;
+        if self.folder_names.is_empty() {
+            split_pattern(self.folder_pattern.trim())?;
+            return check_file_name(&self.folder_pattern, "folder pattern");
+        }
+        for (index, name) in self.folder_names.iter().enumerate() {
+            check_file_name(name, "folder name")?;
+            let earlier = &self.folder_names[..index];
+            if earlier.iter().any(|other| other.trim().eq_ignore_ascii_case(name.trim())) {
+                return Err(format!("Duplicate folder name: {}", name.trim()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Folder name for account `number` (1-based); `None` when the layout has no such account.
+    pub fn folder_name(&self, number: i32) -> Option<String> {
+        if number < 1 {
+            return None;
+        }
+        if !self.folder_names.is_empty() {
+            return self
+                .folder_names
+                .get(number as usize - 1)
+                .map(|name| name.trim().to_string());
+        }
+        let pattern = split_pattern(self.folder_pattern.trim()).ok()?;
+        Some(format!(
+            "{}{:0width$}{}",
+            pattern.prefix,
+            number,
+            pattern.suffix,
+            width = pattern.width
+        ))
+    }
+
+    /// Account number for a folder name, or `None` if the folder does not fit the layout.
+    pub fn account_number(&self, folder_name: &str) -> Option<i32> {
+        if !self.folder_names.is_empty() {
+            return self
+                .folder_names
+                .iter()
+                .position(|name| name.trim().eq_ignore_ascii_case(folder_name))
+                .map(|index| index as i32 + 1);
+        }
+        let pattern = split_pattern(self.folder_pattern.trim()).ok()?;
+        let rest = strip_prefix_ignore_case(folder_name, pattern.prefix)?;
+        let digits = strip_suffix_ignore_case(rest, pattern.suffix)?;
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        // Only the exact name `folder_name` builds counts, so `TG 01` is not account 1 of
+        // `TG {n}` and two folders never share a number.
+        let number = digits.parse::<i32>().ok()?;
+        self.folder_name(number)
+            .is_some_and(|name| name.eq_ignore_ascii_case(folder_name))
+            .then_some(number)
+    }
+
+    /// Whether a process image name is this layout's Telegram executable.
+    pub fn is_exe_name(&self, file_name: &str) -> bool {
+        let exe = self.exe_name.trim();
+        file_name.eq_ignore_ascii_case(exe)
+            || exe
+                .strip_suffix(".exe")
+                .is_some_and(|stem| file_name.eq_ignore_ascii_case(stem))
+    }
+
+    /// Every folder directly under `root` that holds `tdata` or the executable, whatever its
+    /// name. Layout accounts come first by number, then the rest by name.
+    pub fn scan(&self, root: &Path) -> Vec<AccountFolder> {
+        let entries = match fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        let mut found: Vec<AccountFolder> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+            .filter_map(|entry| self.account_folder(&entry.path()))
+            .collect();
+        found.sort_by_cached_key(AccountFolder::sort_key);
+        found
+    }
+
+    /// `path` as an account folder, or `None` when it holds neither `tdata` nor the executable.
+    pub fn account_folder(&self, path: &Path) -> Option<AccountFolder> {
+        let name = path.file_name()?.to_string_lossy().to_string();
+        let has_exe = path.join(self.exe_name.trim()).is_file();
+        let has_tdata = path.join("tdata").is_dir();
+        (has_exe || has_tdata).then(|| AccountFolder {
+            number: self.account_number(&name),
+            name,
+            path: path.to_path_buf(),
+            has_exe,
+            has_tdata,
+        })
+    }
+
+    /// [`scan`](Self::scan) plus the size and modification time of every folder.
+    pub fn discover(&self, root: &Path) -> Vec<AccountIdentity> {
+        self.scan(root)
+            .into_iter()
+            .map(|folder| AccountIdentity {
+                id: account_key(AccountKind::Telegram, &folder.name),
+                exe_path: folder.path.join(self.exe_name.trim()),
+                size_bytes: dir_size(&folder.path),
+                modified_at: modified(&folder.path).max(modified(&folder.path.join("tdata"))),
+                number: folder.number,
+                folder: folder.name,
+                has_exe: folder.has_exe,
+                has_tdata: folder.has_tdata,
+            })
+            .collect()
+    }
+
+    /// Finds the folder an [`AccountRef`] points at. Numbers and numeric strings go through
+    /// the layout; anything else must name an existing folder directly under `root`.
+    pub fn resolve(&self, root: &Path, account: &AccountRef) -> Result<ResolvedAccount, String> {
+        let key = match account {
+            AccountRef::Number(number) => return self.resolve_number(*number),
+            AccountRef::Key(key) => key.trim(),
+        };
+        let prefix = account_key(AccountKind::Telegram, "");
+        let folder = strip_prefix_ignore_case(key, &prefix).unwrap_or(key).trim();
+        if let Ok(number) = folder.parse::<i32>() {
+            return self.resolve_number(number);
+        }
+        check_file_name(folder, "account folder")?;
+        if !root.join(folder).is_dir() {
+            return Err(format!("Account folder not found: {}", folder));
+        }
+        Ok(ResolvedAccount {
+            folder: folder.to_string(),
+            number: self.account_number(folder),
+        })
+    }
+
+    fn resolve_number(&self, number: i32) -> Result<ResolvedAccount, String> {
+        let folder = self
+            .folder_name(number)
+            .ok_or_else(|| format!("Account {} has no folder in the account layout", number))?;
+        Ok(ResolvedAccount {
+            folder,
+            number: Some(number),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern_layout(pattern: &str) -> AccountLayout {
+        AccountLayout {
+            folder_pattern: pattern.to_string(),
+            ..AccountLayout::default()
+        }
+    }
+
+    #[test]
+    fn split_pattern_accepts_plain_and_padded_placeholders() {
+        let plain = split_pattern("TG {n}").unwrap();
+        assert_eq!((plain.prefix, plain.width, plain.suffix), ("TG ", 0, ""));
+        let padded = split_pattern("acc_{n:03}_tg").unwrap();
+        assert_eq!((padded.prefix, padded.width, padded.suffix), ("acc_", 3, "_tg"));
+    }
+
+    #[test]
+    fn split_pattern_rejects_malformed_placeholders() {
+        for pattern in ["TG", "TG {n", "TG {n:3}", "TG {n:0}", "TG {n:099}", "{n} {n}", "TG {x}{n}"] {
+            assert!(split_pattern(pattern).is_err(), "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn folder_name_follows_the_layout() {
+        assert_eq!(pattern_layout("TG {n}").folder_name(5), Some("TG 5".to_string()));
+        assert_eq!(pattern_layout("acc_{n:03}").folder_name(7), Some("acc_007".to_string()));
+        assert_eq!(pattern_layout("acc_{n:03}").folder_name(1234), Some("acc_1234".to_string()));
+        assert_eq!(pattern_layout("TG {n}").folder_name(0), None);
+
+        let named = AccountLayout {
+            folder_names: vec!["alice".to_string(), " bob ".to_string()],
+            ..AccountLayout::default()
+        };
+        assert_eq!(named.folder_name(2), Some("bob".to_string()));
+        assert_eq!(named.folder_name(3), None);
+    }
+
+    #[test]
+    fn account_number_only_accepts_names_the_layout_builds() {
+        let plain = pattern_layout("TG {n}");
+        assert_eq!(plain.account_number("TG 12"), Some(12));
+        assert_eq!(plain.account_number("tg 12"), Some(12));
+        assert_eq!(plain.account_number("TG 012"), None);
+        assert_eq!(plain.account_number("TG 0"), None);
+        assert_eq!(plain.account_number("TG 1x"), None);
+        assert_eq!(plain.account_number("TG "), None);
+
+        let padded = pattern_layout("acc_{n:03}");
+        assert_eq!(padded.account_number("acc_007"), Some(7));
+        assert_eq!(padded.account_number("acc_7"), None);
+        assert_eq!(padded.account_number("acc_0007"), None);
+
+        let named = AccountLayout {
+            folder_names: vec!["alice".to_string(), "bob".to_string()],
+            ..AccountLayout::default()
+        };
+        assert_eq!(named.account_number("Bob"), Some(2));
+        assert_eq!(named.account_number("carol"), None);
+    }
+}
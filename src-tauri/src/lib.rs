@@ -2,7 +2,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock};
 use rand::seq::SliceRandom;
 use sysinfo::System;
 use tauri::Emitter;
@@ -30,10 +30,38 @@ use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, INVALID_HANDLE_V
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 use serde::{Deserialize, Serialize};
 
-static TELEGRAM_LAUNCH_CANCELLED: AtomicBool = AtomicBool::new(false);
+mod accounts;
+mod deeplink;
+mod jobs;
+mod journal;
+mod launcher;
+mod layout;
+mod links;
+mod paths;
+mod scanner;
+mod sessions;
+mod settings;
+mod store;
+mod tasks;
+mod tdata;
+mod templating;
+mod watchdog;
+
+use accounts::{AccountKind, AccountRecord};
+use journal::{ActionKind, ActionOutcome};
+use settings::AppSettings;
+
+/// Cancel flags of the interactive Telegram launches in flight; scheduled jobs keep their own.
+static TELEGRAM_LAUNCH_CANCELS: Mutex<Vec<Arc<AtomicBool>>> = Mutex::new(Vec::new());
 #[cfg(windows)]
 static CHROME_PROFILE_HWNDS: OnceLock<Mutex<HashMap<String, isize>>> = OnceLock::new();
 static APP_STARTED_AT: OnceLock<std::time::Instant> = OnceLock::new();
+static RUNNING_LAUNCH_JOBS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+static SETTINGS_STORE: OnceLock<Arc<settings::SettingsStore>> = OnceLock::new();
+
+/// Poll interval when the settings folder cannot be watched.
+const SETTINGS_WATCH_SECS: u64 = 2;
+const SETTINGS_WATCH_DEBOUNCE_MS: u64 = 250;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TelegramLink {
@@ -50,124 +78,531 @@ pub struct TelegramLink {
     pub ref_link: String,
     #[serde(default)]
     pub mixed: String,
+    /// `resolve` (default), `startgroup`, `startchannel`, `join`, `addlist`, `msg_url`, `post`,
+    /// `privatepost` or `voicechat`. `ref_link` carries the kind's payload (start parameter,
+    /// invite hash, folder slug or URL to share); `privatepost` takes the numeric channel id
+    /// in `app_name`.
+    #[serde(default)]
+    pub link_kind: String,
+    /// Rights requested by `startgroup`/`startchannel` links, e.g. `post_messages,invite_users`.
+    #[serde(default)]
+    pub admin_rights: String,
+    #[serde(default)]
+    pub post_id: String,
+    /// Prefilled message for `msg_url` links.
+    #[serde(default)]
+    pub message_text: String,
+    /// CSV with one row per account, read by `{csv:column}` placeholders in `ref_link`.
+    #[serde(default)]
+    pub payload_csv: String,
+    /// Follow-up links sent after this one, in order, e.g. join a channel and then open a bot.
+    #[serde(default)]
+    pub steps: Vec<LinkStep>,
 }
 
+/// One follow-up link of a multi-step sequence. Nested `steps` are rejected; `payload_csv`
+/// is ignored.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct AppSettings {
-    #[serde(rename = "telegramThreads", default)]
-    telegram_threads: String,
-    #[serde(rename = "telegramFolderPath", default)]
-    telegram_folder_path: String,
-    #[serde(rename = "telegramLaunchSpeed", default)]
-    telegram_launch_speed: String,
-    #[serde(rename = "chromeThreads", default)]
-    chrome_threads: String,
-    #[serde(rename = "chromeFolderPath", default)]
-    chrome_folder_path: String,
+pub struct LinkStep {
+    #[serde(flatten)]
+    pub link: TelegramLink,
+    /// Wait before this step; defaults to the launch speed's post-link delay.
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
 }
 
-fn normalize_launch_speed_profile(raw: &str) -> &'static str {
-    match raw.trim().to_lowercase().as_str() {
-        "fast" => "fast",
-        "balanced" => "balanced",
-        _ => "conservative",
-    }
+/// How long a freshly spawned client may take to come up before its deep link is abandoned.
+const TELEGRAM_READY_TIMEOUT_MS: u64 = 30_000;
+
+const WATCHDOG_TICK_SECS: u64 = 15;
+const ACCOUNT_SCAN_TICK_SECS: u64 = 5;
+
+/// Parallel Telegram launch workers; validation keeps this at one or more.
+fn telegram_thread_count(workspace: &settings::Workspace) -> usize {
+    workspace.telegram_threads.max(1) as usize
 }
 
-fn launch_spawn_delays_ms(settings: &AppSettings) -> (u64, u64) {
-    match normalize_launch_speed_profile(&settings.telegram_launch_speed) {
-        "fast" => (1200, 700),
-        "balanced" => (2000, 1200),
-        _ => (3000, 2000),
+fn watchdog_config(settings: &AppSettings) -> watchdog::WatchdogConfig {
+    watchdog::WatchdogConfig {
+        enabled: settings.telegram_watchdog,
+        max_restarts: settings.telegram_watchdog_retries,
+        resend_link: settings.telegram_watchdog_resend_link,
     }
 }
 
 fn settings_file_path() -> PathBuf {
-    if let Ok(appdata) = std::env::var("APPDATA") {
-        return PathBuf::from(appdata)
-            .join("AbuseApp")
-            .join("settings.json");
+    paths::dirs().config.join("settings.json")
+}
+
+fn app_data_file_path(file_name: &str) -> PathBuf {
+    paths::dirs().data.join(file_name)
+}
+
+fn account_registry_path() -> PathBuf {
+    app_data_file_path("accounts.json")
+}
+
+fn record_account_activity(workspace_id: &str, kind: AccountKind, folder_name: &str) {
+    let key = accounts::registry_key(workspace_id, kind, folder_name);
+    let result = accounts::with_registry(&account_registry_path(), |registry| {
+        if let Some(account) = registry.find_mut(&key) {
+            account.last_active = Some(chrono::Utc::now());
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        println!("[LOG] Failed to record activity for {}: {}", key, e);
     }
+}
 
-    std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join(".abuseapp-settings.json")
+fn daily_tasks_path() -> PathBuf {
+    app_data_file_path("daily-tasks.json")
 }
 
-fn load_settings_from_disk() -> AppSettings {
-    let path = settings_file_path();
-    let content = match fs::read_to_string(path) {
-        Ok(c) => c,
-        Err(_) => return AppSettings::default(),
-    };
+const DAILY_TASK_TICK_SECS: u64 = 30;
+
+fn spawn_daily_task_ticker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(DAILY_TASK_TICK_SECS));
+        loop {
+            interval.tick().await;
+            let due = match tasks::with_tasks(&daily_tasks_path(), |store| Ok(store.take_due(chrono::Utc::now()))) {
+                Ok(due) => due,
+                Err(e) => {
+                    println!("[LOG] Daily task tick failed: {}", e);
+                    continue;
+                }
+            };
+
+            for task in due {
+                let body = if task.description.trim().is_empty() {
+                    task.title.clone()
+                } else {
+                    task.description.clone()
+                };
+                if let Err(e) = send_reminder_notification(app.clone(), task.title.clone(), body) {
+                    println!("[LOG] Failed to send reminder for task {}: {}", task.id, e);
+                }
+                let _ = app.emit("daily-task-due", &task);
+            }
+        }
+    });
+}
 
-    serde_json::from_str::<AppSettings>(&content).unwrap_or_default()
+fn launch_jobs_path() -> PathBuf {
+    app_data_file_path("launch-jobs.json")
 }
 
-fn save_settings_to_disk(settings: &AppSettings) -> Result<(), String> {
-    let path = settings_file_path();
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+const LAUNCH_JOB_TICK_SECS: u64 = 20;
+
+/// Running job ids with the cancel flag of their launch.
+fn running_launch_jobs() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    RUNNING_LAUNCH_JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The workspace a job was saved in; jobs from before workspaces existed use the active one.
+fn job_workspace(job: &jobs::LaunchJob) -> Result<settings::Workspace, String> {
+    let settings = current_settings();
+    if job.workspace_id.is_empty() {
+        return Ok(settings.active().clone());
+    }
+    settings
+        .workspaces
+        .into_iter()
+        .find(|workspace| workspace.id == job.workspace_id)
+        .ok_or_else(|| format!("Workspace {} of job {} no longer exists", job.workspace_id, job.name))
+}
+
+async fn run_launch_job(app: tauri::AppHandle, job: jobs::LaunchJob, cancel: Arc<AtomicBool>) -> jobs::JobRun {
+    let started_at = chrono::Utc::now();
+    let _ = app.emit("launch-job-started", &job);
+    println!("[LOG] Running scheduled launch job {} ({})", job.name, job.id);
+
+    let mut report: Option<launcher::LaunchReport> = None;
+    let mut chrome: Option<ChromeLaunchResult> = None;
+    let result: Result<(), String> = match (job_workspace(&job), job.target.clone()) {
+        (Err(e), _) => Err(e),
+        (
+            Ok(workspace),
+            jobs::LaunchJobTarget::Telegram {
+                link_params,
+                start_range,
+                end_range,
+                telegram_folder_path,
+            },
+        ) => {
+            let folder = if telegram_folder_path.trim().is_empty() {
+                workspace.telegram_folder_path.clone()
+            } else {
+                telegram_folder_path
+            };
+            let mut profile_ids: Vec<i32> = (start_range..=end_range).collect();
+            if link_params.mixed == "yes" {
+                profile_ids.shuffle(&mut rand::thread_rng());
+            }
+            let request = TelegramLaunchRequest {
+                link_params: *link_params,
+                profiles: profile_ids,
+                folders: HashMap::new(),
+                telegram_folder_path: folder,
+            };
+            run_telegram_launch(Some(app.clone()), workspace, request, None, Some(cancel))
+                .await
+                .map(|launched| report = Some(launched))
+        }
+        (
+            Ok(workspace),
+            jobs::LaunchJobTarget::Chrome {
+                chrome_folder_path,
+                start_range,
+                end_range,
+                mixed,
+                target_url,
+            },
+        ) => {
+            let folder = if chrome_folder_path.trim().is_empty() {
+                workspace.chrome_folder_path.clone()
+            } else {
+                chrome_folder_path
+            };
+            launch_chrome_profiles(app.clone(), folder, start_range, end_range, mixed, target_url, Some(workspace.id))
+                .await
+                .map(|mut launched| {
+                    report = launched.report.take();
+                    chrome = Some(launched);
+                })
+        }
+    };
+
+    jobs::JobRun {
+        id: uuid::Uuid::new_v4().to_string(),
+        job_id: job.id.clone(),
+        job_name: job.name.clone(),
+        started_at,
+        finished_at: chrono::Utc::now(),
+        outcome: if result.is_ok() {
+            jobs::JobRunOutcome::Success
+        } else {
+            jobs::JobRunOutcome::Failed
+        },
+        pids: report.as_ref().map(|report| report.pids.clone()).unwrap_or_default(),
+        chrome,
+        report,
+        error: result.err(),
     }
+}
 
-    let body = serde_json::to_string_pretty(settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+fn start_launch_job(app: tauri::AppHandle, job: jobs::LaunchJob) -> bool {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let inserted = running_launch_jobs()
+        .lock()
+        .map(|mut running| {
+            if running.contains_key(&job.id) {
+                return false;
+            }
+            running.insert(job.id.clone(), cancel.clone());
+            true
+        })
+        .unwrap_or(false);
+    if !inserted {
+        println!("[LOG] Launch job {} is already running, skipping", job.id);
+        return false;
+    }
 
-    fs::write(path, body).map_err(|e| format!("Failed to write settings: {}", e))
+    tauri::async_runtime::spawn(async move {
+        let job_id = job.id.clone();
+        let run = run_launch_job(app.clone(), job, cancel).await;
+        if let Err(e) = jobs::with_jobs(&launch_jobs_path(), |store| {
+            store.push_run(run.clone());
+            Ok(())
+        }) {
+            println!("[LOG] Failed to store launch job run: {}", e);
+        }
+        if let Ok(mut running) = running_launch_jobs().lock() {
+            running.remove(&job_id);
+        }
+        let _ = app.emit("launch-job-finished", &run);
+    });
+    true
 }
 
+fn spawn_launch_job_ticker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(LAUNCH_JOB_TICK_SECS));
+        loop {
+            interval.tick().await;
+            let due = match jobs::with_jobs(&launch_jobs_path(), |store| Ok(store.take_due(chrono::Utc::now()))) {
+                Ok(due) => due,
+                Err(e) => {
+                    println!("[LOG] Launch job tick failed: {}", e);
+                    continue;
+                }
+            };
+            for job in due {
+                start_launch_job(app.clone(), job);
+            }
+        }
+    });
+}
 
-fn is_likely_logged_out(tdata_path: &Path) -> bool {
-    let entries = match fs::read_dir(tdata_path) {
-        Ok(entries) => entries,
-        Err(_) => return true,
+/// Relaunches one crashed account through the launch engine and reports the attempt.
+async fn restart_watched_account(
+    app: tauri::AppHandle,
+    account: watchdog::WatchedAccount,
+    attempt: u32,
+    config: watchdog::WatchdogConfig,
+) {
+    let target = account.folder.clone();
+    println!("[LOG] Watchdog relaunching {} (attempt {}/{})", target, attempt, config.max_restarts);
+    let link = if config.resend_link { account.link.clone() } else { None };
+    let link_resent = link.is_some();
+    let settings = current_settings();
+    let workspace = settings.workspace_for_telegram_folder(&account.telegram_folder_path);
+    let timing = workspace.telegram_timing_profile();
+    let plan = launcher::LaunchPlan {
+        profiles: vec![account.profile],
+        telegram_folder_path: account.telegram_folder_path.clone(),
+        layout: workspace.account_layout.clone(),
+        folders: HashMap::from([(account.profile, account.folder.clone())]),
+        readiness: link.as_ref().map(|_| launcher::ReadinessCheck {
+            grace_ms: timing.spawn_delay_ms,
+            timeout_ms: TELEGRAM_READY_TIMEOUT_MS,
+        }),
+        links: link
+            .map(|link| HashMap::from([(account.profile, vec![launcher::PlannedLink { link, delay_ms: None }])]))
+            .unwrap_or_default(),
+        already_spawned: HashSet::new(),
+        start_in_tray: account.start_in_tray,
+        timing,
+        concurrency: 1,
     };
+    let report = launcher::LaunchEngine::new(plan, None, telegram_launch_sink(None, workspace.id.clone(), None))
+        .run()
+        .await;
+
+    let succeeded = report.launched == 1;
+    let error = report.profiles.first().and_then(|profile| profile.error.clone());
+    watchdog::restart_finished(&account.account_dir, succeeded);
+    refresh_account_processes(&app).await;
+    journal_action(
+        ActionKind::TelegramRestart,
+        &target,
+        if succeeded { ActionOutcome::Success } else { ActionOutcome::Failed },
+        error,
+    );
+    let _ = app.emit(
+        "telegram-watchdog-restart",
+        watchdog::RestartEvent {
+            profile: account.profile,
+            attempt,
+            max_restarts: config.max_restarts,
+            link_resent,
+            succeeded,
+            report: Some(report),
+        },
+    );
+}
 
-    let mut names: Vec<String> = Vec::new();
-    for entry in entries.flatten() {
-        let file_name = entry.file_name().to_string_lossy().to_lowercase();
-        names.push(file_name);
+fn spawn_telegram_watchdog(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(WATCHDOG_TICK_SECS));
+        loop {
+            interval.tick().await;
+            let config = watchdog_config(&current_settings());
+            if !config.enabled {
+                continue;
+            }
+            let actions = match tauri::async_runtime::spawn_blocking(move || watchdog::check(config)).await {
+                Ok(actions) => actions,
+                Err(e) => {
+                    println!("[LOG] Watchdog pass failed: {}", e);
+                    continue;
+                }
+            };
+            for action in actions {
+                match action {
+                    watchdog::WatchdogAction::Restart { account, attempt } => {
+                        tauri::async_runtime::spawn(restart_watched_account(app.clone(), account, attempt, config));
+                    }
+                    watchdog::WatchdogAction::GiveUp { account } => {
+                        let target = account.folder.clone();
+                        println!("[LOG] Watchdog gave up on {} after {} restarts", target, account.restarts);
+                        journal_action(
+                            ActionKind::TelegramRestart,
+                            &target,
+                            ActionOutcome::Skipped,
+                            Some(format!("Restart budget of {} exhausted", config.max_restarts)),
+                        );
+                        let _ = app.emit("telegram-watchdog-gave-up", &account);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Keeps the account scan of the active workspace warm so `get_account_stats` answers from
+/// memory, and tells the webview which accounts appeared, vanished or changed status.
+fn spawn_account_scanner(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(ACCOUNT_SCAN_TICK_SECS));
+        loop {
+            interval.tick().await;
+            let workspace = current_settings().active().clone();
+            let diffs = tauri::async_runtime::spawn_blocking(move || {
+                let root = PathBuf::from(workspace.telegram_folder_path.trim());
+                let active = (!root.as_os_str().is_empty() && root.is_dir()).then_some((root.as_path(), &workspace.account_layout));
+                scanner::refresh(active)
+            })
+            .await;
+            match diffs {
+                Ok(diffs) => {
+                    for diff in diffs {
+                        let _ = app.emit("telegram-accounts-changed", &diff);
+                    }
+                }
+                Err(e) => println!("[LOG] Account scan failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Re-matches processes in the account scan after a launch or close, so the running counts
+/// do not wait for the next background pass.
+async fn refresh_account_processes(app: &tauri::AppHandle) {
+    match tauri::async_runtime::spawn_blocking(scanner::refresh_processes).await {
+        Ok(diffs) => {
+            for diff in diffs {
+                let _ = app.emit("telegram-accounts-changed", &diff);
+            }
+        }
+        Err(e) => println!("[LOG] Account process refresh failed: {}", e),
     }
+}
 
-    if names.is_empty() {
-        return true;
+/// The default workspace keeps the original `links.json`; others get their own folder.
+fn link_catalog_path(workspace_id: &str) -> PathBuf {
+    if workspace_id == settings::DEFAULT_WORKSPACE_ID {
+        app_data_file_path("links.json")
+    } else {
+        workspace_dir(workspace_id).join("links.json")
     }
+}
 
-    let ban_markers = [
-        "deleted",
-        "banned",
-        "suspended",
-        "restricted",
-        "unauthorized",
-        "logout",
-        "blocked",
-    ];
+fn workspace_dir(workspace_id: &str) -> PathBuf {
+    app_data_file_path("workspaces").join(workspace_id)
+}
 
-    if names
-        .iter()
-        .any(|name| ban_markers.iter().any(|marker| name.contains(marker)))
-    {
-        return true;
+/// The named workspace (by id or name), or the active one when `key` is blank.
+fn resolve_workspace(key: Option<&str>) -> Result<settings::Workspace, String> {
+    current_settings().workspace(key).cloned()
+}
+
+fn workspace_catalog_path(workspace: Option<&str>) -> Result<PathBuf, String> {
+    Ok(link_catalog_path(&resolve_workspace(workspace)?.id))
+}
+
+fn launch_sessions_dir() -> PathBuf {
+    app_data_file_path("launch-sessions")
+}
+
+fn journal_dir() -> PathBuf {
+    app_data_file_path("journal")
+}
+
+fn journal_action(kind: ActionKind, target: &str, outcome: ActionOutcome, error: Option<String>) {
+    let event = journal::ActionEvent::new(kind, target, outcome, error);
+    if let Err(e) = journal::append(&journal_dir(), &event) {
+        println!("[LOG] Failed to append action journal: {}", e);
     }
+}
 
-    let has_core = names.iter().any(|name| {
-        name.contains("session")
-            || name.contains("user")
-            || name.contains("key")
-            || name.contains("map")
-            || name.contains("setting")
-    });
+/// Shared with Tauri as managed state; helpers without an `AppHandle` read it directly.
+fn settings_store() -> &'static Arc<settings::SettingsStore> {
+    SETTINGS_STORE.get_or_init(|| Arc::new(settings::SettingsStore::open(settings_file_path())))
+}
 
-    if !has_core {
-        return true;
+fn current_settings() -> AppSettings {
+    settings_store().get()
+}
+
+/// Watches the settings file so edits made outside the app reach the running commands and the
+/// webview. The folder is watched rather than the file, since saves replace the file; when no
+/// watcher can be created the file is polled instead.
+fn spawn_settings_watcher(app: tauri::AppHandle) {
+    let path = settings_file_path();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+    let watcher = watch_settings_file(&path, tx);
+    if let Err(e) = &watcher {
+        println!("[LOG] {}; polling the settings file instead", e);
     }
+    tauri::async_runtime::spawn(async move {
+        let _watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(_) => {
+                let mut interval =
+                    tokio::time::interval(tokio::time::Duration::from_secs(SETTINGS_WATCH_SECS));
+                loop {
+                    interval.tick().await;
+                    if let Some(settings) = settings_store().reload_if_changed() {
+                        let _ = app.emit("settings-changed", &settings);
+                    }
+                }
+            }
+        };
+        while rx.recv().await.is_some() {
+            // Editors often save in several writes; let them land before reading.
+            tokio::time::sleep(tokio::time::Duration::from_millis(SETTINGS_WATCH_DEBOUNCE_MS)).await;
+            while rx.try_recv().is_ok() {}
+            if let Some(settings) = settings_store().reload_if_changed() {
+                let _ = app.emit("settings-changed", &settings);
+            }
+        }
+    });
+}
 
-    names.len() <= 2
+fn watch_settings_file(
+    path: &Path,
+    changed: tokio::sync::mpsc::UnboundedSender<()>,
+) -> Result<notify::RecommendedWatcher, String> {
+    use notify::Watcher;
+
+    let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create settings folder {}: {}", dir.display(), e))?;
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let relevant = match result {
+            Ok(event) if matches!(event.kind, notify::EventKind::Access(_)) => false,
+            Ok(event) => {
+                event.need_rescan()
+                    || event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name() == file_name.as_deref())
+            }
+            Err(_) => true,
+        };
+        if relevant {
+            let _ = changed.send(());
+        }
+    })
+    .map_err(|e| format!("Failed to create settings watcher: {}", e))?;
+    watcher
+        .watch(&dir, notify::RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+    Ok(watcher)
 }
 
 fn list_running_telegram_processes() -> Vec<(u32, String, String)> {
-    let mut system = System::new_all();
+    // Workspaces may run a renamed Telegram build; its image name counts as Telegram too.
+    let layouts: Vec<layout::AccountLayout> = current_settings()
+        .workspaces
+        .into_iter()
+        .map(|workspace| workspace.account_layout)
+        .collect();
+    // Only the process table is needed; `System::new_all` also loads CPU, memory and disk data.
+    let mut system = System::new();
     system.refresh_processes();
 
     system
@@ -185,7 +620,8 @@ fn list_running_telegram_processes() -> Vec<(u32, String, String)> {
             let is_telegram = name_lower.contains("telegram")
                 || path_lower.ends_with("\\telegram.exe")
                 || path_lower.ends_with("/telegram")
-                || path_lower.contains("\\telegram desktop\\");
+                || path_lower.contains("\\telegram desktop\\")
+                || layouts.iter().any(|layout| layout.is_exe_name(&name));
 
             if !is_telegram {
                 return None;
@@ -670,7 +1106,7 @@ fn list_running_chrome_processes() -> Vec<(u32, String, String, Vec<String>)> {
         }
     }
 
-    let mut system = System::new_all();
+    let mut system = System::new();
     system.refresh_processes();
 
     system
@@ -1277,12 +1713,14 @@ fn try_infer_and_close_profile_by_single_unmapped_window(
     None
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ChromeLaunchResult {
     selected: usize,
     started: usize,
     skipped: usize,
     failed: usize,
+    #[serde(default)]
+    report: Option<launcher::LaunchReport>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -1309,15 +1747,51 @@ fn normalize_path_for_match(value: &str) -> String {
     normalized.trim_end_matches('/').to_string()
 }
 
-fn build_account_dirs(account_ids: &[i32], root_raw: &str, root_norm: &str) -> Vec<String> {
-    account_ids
+/// Turns account references into launch profile ids. Accounts that fit the folder layout keep
+/// their number; other folders get negative stand-in ids. Every folder the layout would not
+/// build from the id (stand-ins, or a differently spelled numbered folder) is mapped in the
+/// result.
+fn resolve_launch_accounts(
+    telegram_folder_path: &str,
+    accounts: &[layout::AccountRef],
+) -> Result<(Vec<i32>, HashMap<i32, String>), String> {
+    let layout = current_settings()
+        .workspace_for_telegram_folder(telegram_folder_path)
+        .account_layout
+        .clone();
+    let root = Path::new(telegram_folder_path.trim());
+    let mut profiles = Vec::with_capacity(accounts.len());
+    let mut folders = HashMap::new();
+    for account in accounts {
+        let resolved = layout.resolve(root, account)?;
+        let profile = match resolved.number {
+            Some(number) => number,
+            None => -(profiles.iter().filter(|profile| **profile < 0).count() as i32) - 1,
+        };
+        if layout.folder_name(profile).as_deref() != Some(resolved.folder.as_str()) {
+            folders.insert(profile, resolved.folder);
+        }
+        profiles.push(profile);
+    }
+    Ok((profiles, folders))
+}
+
+fn resolve_accounts(
+    layout: &layout::AccountLayout,
+    root_raw: &str,
+    accounts: &[layout::AccountRef],
+) -> Result<Vec<layout::ResolvedAccount>, String> {
+    accounts
         .iter()
-        .filter_map(|account_id| {
-            let dir = normalize_path_for_match(
-                &Path::new(root_raw)
-                    .join(format!("TG {}", account_id))
-                    .to_string_lossy(),
-            );
+        .map(|account| layout.resolve(Path::new(root_raw), account))
+        .collect()
+}
+
+fn build_account_dirs(accounts: &[layout::ResolvedAccount], root_raw: &str, root_norm: &str) -> Vec<String> {
+    accounts
+        .iter()
+        .filter_map(|account| {
+            let dir = normalize_path_for_match(&Path::new(root_raw).join(&account.folder).to_string_lossy());
             if dir.is_empty() || !dir.starts_with(root_norm) {
                 None
             } else {
@@ -1327,6 +1801,23 @@ fn build_account_dirs(account_ids: &[i32], root_raw: &str, root_norm: &str) -> V
         .collect()
 }
 
+/// Both arguments must already be passed through `normalize_path_for_match`.
+fn path_in_account_dir(path_norm: &str, account_dir: &str) -> bool {
+    path_norm == account_dir
+        || path_norm
+            .strip_prefix(account_dir)
+            .map(|rest| rest.starts_with('/'))
+            .unwrap_or(false)
+}
+
+/// Normalized exe paths of every running Telegram client.
+fn running_telegram_paths() -> Vec<String> {
+    list_running_telegram_processes()
+        .into_iter()
+        .map(|(_pid, _name, path)| normalize_path_for_match(&path))
+        .collect()
+}
+
 fn get_pids_for_account_dirs(account_dirs: &[String]) -> Vec<u32> {
     if account_dirs.is_empty() {
         return Vec::new();
@@ -1341,7 +1832,7 @@ fn get_pids_for_account_dirs(account_dirs: &[String]) -> Vec<u32> {
             continue;
         }
         let path_norm = normalize_path_for_match(&path);
-        if account_dirs.iter().any(|dir| path_norm == *dir || path_norm.starts_with(&(dir.clone() + "/"))) {
+        if account_dirs.iter().any(|dir| path_in_account_dir(&path_norm, dir)) {
             target_pids.push(pid);
         }
     }
@@ -1359,7 +1850,9 @@ fn set_explicit_app_user_model_id() {
 
 pub fn run() {
   let _ = APP_STARTED_AT.get_or_init(std::time::Instant::now);
-  let is_autostart = std::env::args().any(|arg| arg == "--autostart");
+  let args: Vec<String> = std::env::args().collect();
+  let is_autostart = args.iter().any(|arg| arg == "--autostart");
+  paths::init(&args);
 
   #[cfg(windows)]
   if is_autostart {
@@ -1428,6 +1921,18 @@ pub fn run() {
 
         // Keep tray alive for the lifetime of the app.
         app.manage(tray_icon);
+        app.manage(settings_store().clone());
+        spawn_settings_watcher(app.handle().clone());
+        // Before any ticker can start a session that the sweep would wrongly flag.
+        if let Err(e) = sessions::mark_interrupted(&launch_sessions_dir()) {
+            println!("[LOG] Failed to mark interrupted launch sessions: {}", e);
+        }
+
+        // Reminders are driven from Rust so they fire while the webview is hidden in the tray.
+        spawn_daily_task_ticker(app.handle().clone());
+        spawn_launch_job_ticker(app.handle().clone());
+        spawn_telegram_watchdog(app.handle().clone());
+        spawn_account_scanner(app.handle().clone());
 
         if let Some(window) = app.get_webview_window("main") {
             let _ = window.center();
@@ -1452,15 +1957,43 @@ pub fn run() {
       launch_single_account,
       launch_accounts_batch,
       launch_accounts_for_profiles,
+      get_launch_sessions,
+      resume_launch_session,
+      delete_launch_session,
+      get_watchdog_status,
       get_available_links,
+      get_link_catalog,
+      save_link_entry,
+      delete_link_entry,
+      reorder_link_entries,
+      export_link_catalog,
+      import_link_catalog,
       build_telegram_link,
+      parse_telegram_link,
       get_settings,
+      get_app_directories,
       save_settings,
+      get_workspaces,
+      save_workspace,
+      delete_workspace,
+      set_active_workspace,
       get_account_stats,
+      get_account_stats_detailed,
+      discover_telegram_accounts,
+      inspect_telegram_account,
       update_account_status,
+      update_account_details,
       get_recent_actions,
       get_daily_tasks,
       update_daily_task,
+      save_daily_task,
+      delete_daily_task,
+      get_launch_jobs,
+      save_launch_job,
+      delete_launch_job,
+      cancel_launch_job,
+      run_launch_job_now,
+      get_launch_job_runs,
       minimize_window,
       maximize_window,
       show_window,
@@ -1493,34 +2026,56 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Adds records for the workspace's Telegram and Chrome folders and flags missing ones.
+fn sync_account_folders(registry: &mut accounts::AccountRegistry, workspace: &settings::Workspace) {
+    let telegram_root = PathBuf::from(workspace.telegram_folder_path.trim());
+    if !workspace.telegram_folder_path.trim().is_empty() && telegram_root.is_dir() {
+        let folders: Vec<String> = workspace
+            .account_layout
+            .scan(&telegram_root)
+            .into_iter()
+            .map(|folder| folder.name)
+            .collect();
+        registry.sync_folders(&workspace.id, AccountKind::Telegram, &telegram_root, &folders);
+    }
+
+    let chrome_root = if workspace.chrome_folder_path.trim().is_empty() {
+        default_chrome_user_data_dir()
+    } else {
+        Some(PathBuf::from(workspace.chrome_folder_path.trim()))
+    };
+    if let Some(root) = chrome_root.as_deref().filter(|root| root.is_dir()) {
+        let folders = accounts::scan_account_folders(AccountKind::Chrome, root);
+        registry.sync_folders(&workspace.id, AccountKind::Chrome, root, &folders);
+    }
+}
+
+/// Edits one record of `workspace`. A record the registry does not know yet (e.g. before the
+/// webview ever called `get_accounts`) is picked up by syncing that workspace first.
+fn update_account_record<T>(
+    workspace: &settings::Workspace,
+    key: &str,
+    update: impl FnOnce(&mut AccountRecord) -> T,
+) -> Result<T, String> {
+    accounts::with_registry(&account_registry_path(), |registry| {
+        if registry.find_mut(key).is_none() {
+            sync_account_folders(registry, workspace);
+        }
+        let account = registry
+            .find_mut(key)
+            .filter(|account| account.workspace_id == workspace.id)
+            .ok_or_else(|| format!("Account not found: {}", key))?;
+        Ok(update(account))
+    })
+}
+
 #[tauri::command]
-async fn get_accounts() -> Result<Vec<serde_json::Value>, String> {
-    // Mock data for testing
-    let accounts = vec![
-        serde_json::json!({
-            "id": "1",
-            "name": "Account 1",
-            "status": "active",
-            "type": "telegram",
-            "lastActive": "2024-02-09T10:30:00Z"
-        }),
-        serde_json::json!({
-            "id": "2", 
-            "name": "Account 2",
-            "status": "blocked",
-            "type": "chrome",
-            "lastActive": "2024-02-08T15:45:00Z"
-        }),
-        serde_json::json!({
-            "id": "3",
-            "name": "Account 3", 
-            "status": "active",
-            "type": "telegram",
-            "lastActive": "2024-02-07T12:20:00Z"
-        })
-    ];
-    
-    Ok(accounts)
+async fn get_accounts(workspace: Option<String>) -> Result<Vec<AccountRecord>, String> {
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    accounts::with_registry(&account_registry_path(), |registry| {
+        sync_account_folders(registry, &workspace);
+        Ok(registry.workspace_accounts(&workspace.id, &workspace.account_layout))
+    })
 }
 
 #[tauri::command]
@@ -1529,392 +2084,731 @@ async fn launch_accounts(account_ids: Vec<String>) -> Result<String, String> {
     Ok(format!("Launched {} accounts", account_ids.len()))
 }
 
-#[tauri::command]
-async fn launch_single_account(
-    account_id: i32,
-    telegram_folder_path: String,
-) -> Result<u32, String> {
-    use std::process::Command;
-    println!("[LOG] Launching TG {}", account_id);
-    
-    let telegram_exe_path = Path::new(&telegram_folder_path)
-        .join(format!("TG {}", account_id))
-        .join("Telegram.exe");
-    
-    if telegram_exe_path.exists() {
-        match Command::new(&telegram_exe_path)
-            .spawn()
-        {
-            Ok(child) => {
-                println!("[LOG] TG {} launched without params", account_id);
-                Ok(child.id())
+/// Forwards launch engine events to the webview and mirrors each outcome into the
+/// action journal, the account registry and, for batch launches, the session checkpoint.
+fn telegram_launch_sink(
+    app: Option<tauri::AppHandle>,
+    workspace_id: String,
+    session_id: Option<String>,
+) -> launcher::LaunchEventSink {
+    Arc::new(move |event| match event {
+        launcher::LaunchEvent::Progress(progress) => {
+            if let Some(app) = &app {
+                let _ = app.emit("telegram-launch-progress", progress);
             }
-            Err(e) => {
-                println!("[LOG] Failed to launch TG {}: {}", account_id, e);
-                Err(format!("Failed to launch account {}: {}", account_id, e))
+        }
+        launcher::LaunchEvent::Outcome(outcome) => {
+            let target = outcome.target.as_str();
+            if outcome.pid.is_some() {
+                record_account_activity(&workspace_id, AccountKind::Telegram, target);
+            }
+            // A failed deep link leaves the spawned client running, so the launch itself
+            // only failed when nothing was spawned.
+            match outcome.status {
+                launcher::ProfileStatus::Launched => {
+                    journal_action(ActionKind::TelegramLaunch, target, ActionOutcome::Success, None);
+                }
+                launcher::ProfileStatus::Failed if outcome.pid.is_some() => {
+                    journal_action(ActionKind::TelegramLaunch, target, ActionOutcome::Success, None);
+                }
+                launcher::ProfileStatus::Failed => {
+                    journal_action(
+                        ActionKind::TelegramLaunch,
+                        target,
+                        ActionOutcome::Failed,
+                        outcome.error.clone(),
+                    );
+                }
+                launcher::ProfileStatus::Skipped => {
+                    journal_action(ActionKind::TelegramLaunch, target, ActionOutcome::Skipped, None);
+                }
+                launcher::ProfileStatus::Cancelled => {
+                    journal_action(ActionKind::TelegramCancel, target, ActionOutcome::Cancelled, None);
+                }
+            }
+            match outcome.deep_link {
+                launcher::DeepLinkStatus::Sent => {
+                    journal_action(ActionKind::TelegramDeepLink, target, ActionOutcome::Success, None);
+                }
+                launcher::DeepLinkStatus::Partial if outcome.status == launcher::ProfileStatus::Cancelled => {
+                    journal_action(ActionKind::TelegramDeepLink, target, ActionOutcome::Cancelled, None);
+                }
+                launcher::DeepLinkStatus::Failed | launcher::DeepLinkStatus::Partial => {
+                    journal_action(
+                        ActionKind::TelegramDeepLink,
+                        target,
+                        ActionOutcome::Failed,
+                        outcome.error.clone(),
+                    );
+                }
+                launcher::DeepLinkStatus::NotRequested | launcher::DeepLinkStatus::NotSent => {}
+            }
+            if let Some(session_id) = &session_id {
+                let checkpoint = sessions::with_session(&launch_sessions_dir(), session_id, |session| {
+                    session.record_outcome(outcome);
+                    Ok(())
+                });
+                if let Err(e) = checkpoint {
+                    println!("[LOG] Failed to checkpoint launch session {}: {}", session_id, e);
+                }
+            }
+            if let Some(app) = &app {
+                let _ = app.emit("telegram-launch-outcome", outcome);
             }
         }
-    } else {
-        Err(format!("Telegram.exe not found: {}", telegram_exe_path.display()))
+        launcher::LaunchEvent::Complete(report) => {
+            println!(
+                "[LOG] Launch finished: {} launched, {} failed, cancelled={}",
+                report.launched, report.failed, report.cancelled
+            );
+            if let Some(session_id) = &session_id {
+                let finished = sessions::with_session(&launch_sessions_dir(), session_id, |session| {
+                    session.status = if report.cancelled {
+                        sessions::SessionStatus::Cancelled
+                    } else {
+                        sessions::SessionStatus::Completed
+                    };
+                    session.updated_at = chrono::Utc::now();
+                    Ok(session.clone())
+                });
+                match finished {
+                    Ok(session) => {
+                        if let Some(app) = &app {
+                            let _ = app.emit("launch-session-finished", &session);
+                        }
+                    }
+                    Err(e) => println!("[LOG] Failed to finish launch session {}: {}", session_id, e),
+                }
+            }
+            if let Some(app) = &app {
+                let _ = app.emit("telegram-launch-complete", report);
+            }
+        }
+    })
+}
+
+/// The main link followed by every follow-up step, each validated by the link grammar.
+fn build_link_sequence(link_params: &TelegramLink) -> Result<Vec<launcher::PlannedLink>, String> {
+    let mut sequence = vec![launcher::PlannedLink {
+        link: deeplink::build(link_params)?,
+        delay_ms: None,
+    }];
+    for (index, step) in link_params.steps.iter().enumerate() {
+        if !step.link.steps.is_empty() {
+            return Err(format!(
+                "Link step {} has its own steps; list every step on the main link instead",
+                index + 2
+            ));
+        }
+        let link = deeplink::build(&step.link).map_err(|e| format!("Link step {}: {}", index + 2, e))?;
+        sequence.push(launcher::PlannedLink {
+            link,
+            delay_ms: step.delay_ms,
+        });
     }
+    Ok(sequence)
 }
 
-#[tauri::command]
-async fn launch_accounts_batch(
+/// Renders the deep link for every profile up front, so one bad template or CSV row fails the
+/// batch before anything is spawned. `{index}` counts positions in `batch_order`; profiles
+/// missing from `folders` are named by the workspace `layout`.
+fn render_profile_links(
+    link_params: &TelegramLink,
+    profiles: &[i32],
+    batch_order: &[i32],
+    folders: &HashMap<i32, String>,
+    layout: &layout::AccountLayout,
+) -> Result<HashMap<i32, Vec<launcher::PlannedLink>>, String> {
+    if !templating::has_placeholders(link_params) {
+        let sequence = build_link_sequence(link_params)?;
+        for planned in &sequence {
+            println!("[LOG] Generated link: {}", planned.link);
+        }
+        return Ok(profiles.iter().map(|profile| (*profile, sequence.clone())).collect());
+    }
+
+    let columns = match link_params.payload_csv.trim() {
+        "" => None,
+        path => Some(templating::load_account_columns(Path::new(path))?),
+    };
+    let mut positions: HashMap<i32, usize> = HashMap::with_capacity(batch_order.len());
+    for (position, profile) in batch_order.iter().enumerate() {
+        positions.entry(*profile).or_insert(position + 1);
+    }
+    profiles
+        .iter()
+        .map(|&profile| {
+            let target = folders
+                .get(&profile)
+                .cloned()
+                .or_else(|| layout.folder_name(profile))
+                .ok_or_else(|| format!("Account {} has no folder in the account layout", profile))?;
+            let ctx = templating::TemplateContext {
+                profile,
+                index: positions.get(&profile).copied().unwrap_or(1),
+                columns: columns.as_ref(),
+                preview: false,
+            };
+            let sequence = templating::render_link(link_params, &ctx)
+                .and_then(|rendered| build_link_sequence(&rendered))
+                .map_err(|e| format!("{}: {}", target, e))?;
+            for planned in &sequence {
+                println!("[LOG] Generated link for {}: {}", target, planned.link);
+            }
+            Ok((profile, sequence))
+        })
+        .collect()
+}
+
+/// The link and accounts of one Telegram launch under `telegram_folder_path`.
+struct TelegramLaunchRequest {
     link_params: TelegramLink,
-    start_range: i32,
-    end_range: i32,
+    profiles: Vec<i32>,
+    /// Folders for stand-in profile ids of accounts outside the folder layout.
+    folders: HashMap<i32, String>,
     telegram_folder_path: String,
-) -> Result<Vec<u32>, String> {
-    use std::process::Command;
-    use rand::seq::SliceRandom;
-    TELEGRAM_LAUNCH_CANCELLED.store(false, Ordering::SeqCst);
-    let settings = load_settings_from_disk();
-    let (post_spawn_wait_ms, post_link_wait_ms) = launch_spawn_delays_ms(&settings);
+}
 
-    println!("[LOG] Start batch launch for TG accounts");
-    println!("[LOG] Range: {}-{}", start_range, end_range);
-    println!(
-        "[LOG] Link params: api_id={}, app_name={}, app_type={}, ref_link={}, mixed={}",
-        link_params.api_id, link_params.app_name, link_params.app_type, link_params.ref_link, link_params.mixed
-    );
+/// Builds the deep link and runs the request's profiles through the launch engine with the
+/// `workspace` delays. A new checkpointed session is started unless `resume_session_id`
+/// names an existing one.
+async fn run_telegram_launch(
+    app: Option<tauri::AppHandle>,
+    workspace: settings::Workspace,
+    request: TelegramLaunchRequest,
+    resume_session_id: Option<String>,
+    cancel: Option<Arc<AtomicBool>>,
+) -> Result<launcher::LaunchReport, String> {
+    // Interactive launches answer to `request_telegram_launch_cancel`; jobs bring their own flag.
+    let registered = cancel.is_none();
+    let cancel = cancel.unwrap_or_else(register_telegram_launch);
+    let result = run_telegram_launch_with(app.clone(), &workspace, request, resume_session_id, cancel.clone()).await;
+    if registered {
+        unregister_telegram_launch(&cancel);
+    }
+    if let Some(app) = &app {
+        refresh_account_processes(app).await;
+    }
+    result
+}
 
-    let link = build_telegram_link(link_params.clone()).await?;
-    println!("[LOG] Generated link: {}", link);
+fn register_telegram_launch() -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    if let Ok(mut launches) = TELEGRAM_LAUNCH_CANCELS.lock() {
+        launches.push(cancel.clone());
+    }
+    cancel
+}
 
-    let mut profiles: Vec<i32> = (start_range..=end_range).collect();
-
-    if link_params.mixed == "yes" {
-        let mut rng = rand::thread_rng();
-        profiles.shuffle(&mut rng);
-        println!("[LOG] Profiles shuffled");
-    } else {
-        println!("[LOG] Profiles not shuffled");
+fn unregister_telegram_launch(cancel: &Arc<AtomicBool>) {
+    if let Ok(mut launches) = TELEGRAM_LAUNCH_CANCELS.lock() {
+        launches.retain(|flag| !Arc::ptr_eq(flag, cancel));
     }
+}
 
-    let mut launched_pids = Vec::new();
-    let batch_size = settings
-        .telegram_threads
-        .trim()
-        .parse::<usize>()
-        .ok()
-        .filter(|size| *size > 0)
-        .unwrap_or(1);
-    
-    for (i, &profile_num) in profiles.iter().enumerate() {
-        if TELEGRAM_LAUNCH_CANCELLED.load(Ordering::SeqCst) {
-            println!("[LOG] Launch cancelled before TG {}", profile_num);
-            break;
+async fn run_telegram_launch_with(
+    app: Option<tauri::AppHandle>,
+    workspace: &settings::Workspace,
+    request: TelegramLaunchRequest,
+    resume_session_id: Option<String>,
+    cancel: Arc<AtomicBool>,
+) -> Result<launcher::LaunchReport, String> {
+    let TelegramLaunchRequest {
+        link_params,
+        profiles,
+        folders,
+        telegram_folder_path,
+    } = request;
+    let timing = workspace.telegram_timing_profile();
+    let concurrency = telegram_thread_count(workspace);
+    println!("[LOG] Using workspace \"{}\"", workspace.name);
+    println!("[LOG] Launching with {} parallel worker(s)", concurrency);
+
+    println!(
+        "[LOG] Link params: api_id={}, app_name={}, app_type={}, ref_link={}, mixed={}",
+        link_params.api_id, link_params.app_name, link_params.app_type, link_params.ref_link, link_params.mixed
+    );
+    // A resumed session was already claimed as running by `resume_launch_session`.
+    let resumed = match &resume_session_id {
+        Some(session_id) => Some(sessions::with_session(&launch_sessions_dir(), session_id, |session| {
+            Ok(session.clone())
+        })?),
+        None => None,
+    };
+    let batch_order = resumed.as_ref().map(|session| session.profiles.clone()).unwrap_or_else(|| profiles.clone());
+    let mut links = render_profile_links(&link_params, &profiles, &batch_order, &folders, &workspace.account_layout)?;
+    if let Some(session) = &resumed {
+        // Profiles cut off mid-sequence continue with the first step they have not received.
+        for (profile, sent) in &session.steps_sent {
+            if let Some(sequence) = links.get_mut(profile) {
+                sequence.drain(..(*sent).min(sequence.len()));
+            }
         }
-        if i > 0 && i % batch_size == 0 {
-            println!("[LOG] Batch limit reached, returning current PID list");
-            return Ok(launched_pids);
+    }
+
+    let already_spawned = resumed
+        .as_ref()
+        .map(|session| session.spawned.iter().copied().collect())
+        .unwrap_or_default();
+    let session = match resumed {
+        Some(session) => session,
+        None => {
+            let session = sessions::LaunchSession::new(
+                link_params.clone(),
+                telegram_folder_path.clone(),
+                profiles.clone(),
+                folders.clone(),
+            );
+            sessions::create(&launch_sessions_dir(), &session)?;
+            session
         }
-        println!("[LOG] Launching TG {}", profile_num);
-        
-        let telegram_exe_path = Path::new(&telegram_folder_path)
-            .join(format!("TG {}", profile_num))
-            .join("Telegram.exe");
-        
-        if telegram_exe_path.exists() {
-            match Command::new(&telegram_exe_path)
-                .args(if !link_params.app_type.is_empty() { 
-                    vec!["-startintray"] 
-                } else { 
-                    vec![] 
-                })
-                .spawn()
-            {
-                Ok(_child) => {
-                    launched_pids.push(_child.id());
-                    println!("[LOG] TG {} launched without params", profile_num);
-                    tokio::time::sleep(tokio::time::Duration::from_millis(post_spawn_wait_ms)).await;
-                }
-                Err(e) => {
-                    println!("[LOG] Launch error {}: {}", telegram_exe_path.display(), e);
-                }
-            }
-            
-            let args = if !link_params.app_type.is_empty() {
-                vec![link.as_str(), "-startintray"]
-            } else {
-                vec![link.as_str()]
-            };
+    };
+    println!("[LOG] Launch session {}: {} profile(s) to launch", session.id, profiles.len());
+    if let Some(app) = &app {
+        let _ = app.emit("launch-session-started", &session);
+    }
 
-            if TELEGRAM_LAUNCH_CANCELLED.load(Ordering::SeqCst) {
-                println!("[LOG] Launch cancelled before deep link for TG {}", profile_num);
-                break;
-            }
-            
-            match Command::new(&telegram_exe_path)
-                .args(args)
-                .spawn()
-            {
-                Ok(_child) => {
-                    println!("TG {} launched with params {}.", profile_num, link);
-                    tokio::time::sleep(tokio::time::Duration::from_millis(post_link_wait_ms)).await;
-                }
-                Err(e) => {
-                    println!("[LOG] Launch with params failed for TG {}: {}", profile_num, e);
-                }
-            }
-        } else {
-            println!("File not found: {}", telegram_exe_path.display());
+    let start_in_tray = !link_params.app_type.is_empty();
+    let plan = launcher::LaunchPlan {
+        profiles,
+        telegram_folder_path: telegram_folder_path.clone(),
+        layout: workspace.account_layout.clone(),
+        folders,
+        links,
+        already_spawned,
+        start_in_tray,
+        timing,
+        concurrency,
+        readiness: Some(launcher::ReadinessCheck {
+            grace_ms: timing.spawn_delay_ms,
+            timeout_ms: TELEGRAM_READY_TIMEOUT_MS,
+        }),
+    };
+    let sink = telegram_launch_sink(app, workspace.id.clone(), Some(session.id));
+    let engine = launcher::LaunchEngine::new(plan, Some(cancel), sink);
+    let report = engine.run().await;
+
+    for profile in &report.profiles {
+        if profile.status != launcher::ProfileStatus::Launched || profile.pid.is_none() {
+            continue;
         }
+        let account_dir = Path::new(&telegram_folder_path).join(&profile.target);
+        watchdog::watch(
+            profile.profile,
+            &profile.target,
+            &telegram_folder_path,
+            normalize_path_for_match(&account_dir.to_string_lossy()),
+            profile.links.first().cloned(),
+            start_in_tray,
+        );
     }
-    
-    Ok(launched_pids)
+    Ok(report)
 }
 
-#[derive(Debug, Clone, Serialize)]
-struct LaunchProgressPayload {
-    batch_index: usize,
-    batch_total: usize,
-    profile: i32,
+#[tauri::command]
+async fn launch_single_account(
+    app: tauri::AppHandle,
+    account_id: layout::AccountRef,
+    telegram_folder_path: String,
+) -> Result<u32, String> {
+    let workspace = current_settings()
+        .workspace_for_telegram_folder(&telegram_folder_path)
+        .clone();
+    let (profiles, folders) = resolve_launch_accounts(&telegram_folder_path, std::slice::from_ref(&account_id))?;
+    let account_id = profiles[0];
+    let plan = launcher::LaunchPlan {
+        profiles,
+        telegram_folder_path,
+        layout: workspace.account_layout.clone(),
+        folders,
+        links: HashMap::new(),
+        already_spawned: HashSet::new(),
+        start_in_tray: false,
+        timing: workspace.telegram_timing_profile(),
+        concurrency: 1,
+        readiness: None,
+    };
+    let report = launcher::LaunchEngine::new(plan, None, telegram_launch_sink(Some(app.clone()), workspace.id, None))
+        .run()
+        .await;
+    refresh_account_processes(&app).await;
+    let outcome = report
+        .profiles
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Failed to launch account {}", account_id))?;
+    match (outcome.pid, outcome.error) {
+        (Some(pid), _) => Ok(pid),
+        (None, error) => Err(error.unwrap_or_else(|| format!("Failed to launch account {}", account_id))),
+    }
 }
 
 #[tauri::command]
-async fn launch_accounts_for_profiles(
+async fn launch_accounts_batch(
     app: tauri::AppHandle,
     link_params: TelegramLink,
-    profile_ids: Vec<i32>,
+    start_range: i32,
+    end_range: i32,
     telegram_folder_path: String,
-) -> Result<Vec<u32>, String> {
-    use std::process::Command;
-    TELEGRAM_LAUNCH_CANCELLED.store(false, Ordering::SeqCst);
-    let settings = load_settings_from_disk();
-    let (post_spawn_wait_ms, post_link_wait_ms) = launch_spawn_delays_ms(&settings);
+) -> Result<launcher::LaunchReport, String> {
+    println!("[LOG] Start batch launch for TG accounts");
+    println!("[LOG] Range: {}-{}", start_range, end_range);
+
+    let mut profiles: Vec<i32> = (start_range..=end_range).collect();
+    if link_params.mixed == "yes" {
+        profiles.shuffle(&mut rand::thread_rng());
+        println!("[LOG] Profiles shuffled");
+    } else {
+        println!("[LOG] Profiles not shuffled");
+    }
 
+    let workspace = current_settings().workspace_for_telegram_folder(&telegram_folder_path).clone();
+    let request = TelegramLaunchRequest {
+        link_params,
+        profiles,
+        folders: HashMap::new(),
+        telegram_folder_path,
+    };
+    run_telegram_launch(Some(app), workspace, request, None, None).await
+}
+
+#[tauri::command]
+async fn launch_accounts_for_profiles(
+    app: tauri::AppHandle,
+    link_params: TelegramLink,
+    profile_ids: Vec<layout::AccountRef>,
+    telegram_folder_path: String,
+) -> Result<launcher::LaunchReport, String> {
     println!("[LOG] Start batch launch for custom profile list");
     println!("[LOG] Profiles: {:?}", profile_ids);
-    println!(
-        "[LOG] Link params: api_id={}, app_name={}, app_type={}, ref_link={}, mixed={}",
-        link_params.api_id, link_params.app_name, link_params.app_type, link_params.ref_link, link_params.mixed
-    );
 
-    let link = build_telegram_link(link_params.clone()).await?;
-    println!("[LOG] Generated link: {}", link);
+    let (profiles, folders) = resolve_launch_accounts(&telegram_folder_path, &profile_ids)?;
+    let workspace = current_settings().workspace_for_telegram_folder(&telegram_folder_path).clone();
+    let request = TelegramLaunchRequest {
+        link_params,
+        profiles,
+        folders,
+        telegram_folder_path,
+    };
+    run_telegram_launch(Some(app), workspace, request, None, None).await
+}
 
-    let mut launched_pids = Vec::new();
+#[tauri::command]
+async fn get_launch_sessions() -> Result<Vec<sessions::LaunchSession>, String> {
+    sessions::list(&launch_sessions_dir())
+}
 
-    for (index, &profile_num) in profile_ids.iter().enumerate() {
-        if TELEGRAM_LAUNCH_CANCELLED.load(Ordering::SeqCst) {
-            println!("[LOG] Launch cancelled before TG {}", profile_num);
-            break;
+/// Continues a stopped or interrupted session with its original link, skipping every
+/// profile that already received the deep link.
+#[tauri::command]
+async fn resume_launch_session(
+    app: tauri::AppHandle,
+    session_id: String,
+    retry_failed: Option<bool>,
+) -> Result<Option<launcher::LaunchReport>, String> {
+    // Check and claim the session under one lock so two resumes cannot both start it.
+    let (session, previous_status) = sessions::with_session(&launch_sessions_dir(), &session_id, |session| {
+        if session.status == sessions::SessionStatus::Running {
+            return Err(format!("Launch session {} is still running", session_id));
+        }
+        let previous_status = session.status;
+        if !session.remaining_profiles(retry_failed.unwrap_or(false)).is_empty() {
+            session.status = sessions::SessionStatus::Running;
+            session.updated_at = chrono::Utc::now();
         }
-        println!("[LOG] Launching TG {}", profile_num);
+        Ok((session.clone(), previous_status))
+    })?;
 
-        let telegram_exe_path = Path::new(&telegram_folder_path)
-            .join(format!("TG {}", profile_num))
-            .join("Telegram.exe");
+    let remaining = session.remaining_profiles(retry_failed.unwrap_or(false));
+    println!(
+        "[LOG] Resuming launch session {}: {} of {} profile(s) remaining",
+        session_id,
+        remaining.len(),
+        session.profiles.len()
+    );
+    if remaining.is_empty() {
+        return Ok(None);
+    }
 
-        if telegram_exe_path.exists() {
-            match Command::new(&telegram_exe_path)
-                .args(if !link_params.app_type.is_empty() {
-                    vec!["-startintray"]
-                } else {
-                    vec![]
-                })
-                .spawn()
-            {
-                Ok(_child) => {
-                    launched_pids.push(_child.id());
-                    println!("[LOG] TG {} launched without params", profile_num);
-                    tokio::time::sleep(tokio::time::Duration::from_millis(post_spawn_wait_ms)).await;
-                }
-                Err(e) => {
-                    println!("[LOG] Launch error {}: {}", telegram_exe_path.display(), e);
-                }
-            }
+    let workspace = current_settings()
+        .workspace_for_telegram_folder(&session.telegram_folder_path)
+        .clone();
+    let request = TelegramLaunchRequest {
+        link_params: session.link_params,
+        profiles: remaining,
+        folders: session.folders,
+        telegram_folder_path: session.telegram_folder_path,
+    };
+    let result = run_telegram_launch(Some(app), workspace, request, Some(session_id.clone()), None).await;
+    if result.is_err() {
+        // Nothing was launched (e.g. a template failed to render); release the claim.
+        let _ = sessions::with_session(&launch_sessions_dir(), &session_id, |session| {
+            session.status = previous_status;
+            Ok(())
+        });
+    }
+    result.map(Some)
+}
 
-            let args = if !link_params.app_type.is_empty() {
-                vec![link.as_str(), "-startintray"]
-            } else {
-                vec![link.as_str()]
-            };
+#[tauri::command]
+async fn get_watchdog_status() -> Result<Vec<watchdog::WatchedAccount>, String> {
+    Ok(watchdog::snapshot())
+}
 
-            if TELEGRAM_LAUNCH_CANCELLED.load(Ordering::SeqCst) {
-                println!("[LOG] Launch cancelled before deep link for TG {}", profile_num);
-                break;
-            }
+#[tauri::command]
+async fn delete_launch_session(session_id: String) -> Result<(), String> {
+    sessions::delete(&launch_sessions_dir(), &session_id)
+}
 
-            match Command::new(&telegram_exe_path)
-                .args(args)
-                .spawn()
-            {
-                Ok(_child) => {
-                    println!("TG {} launched with params {}.", profile_num, link);
-                    tokio::time::sleep(tokio::time::Duration::from_millis(post_link_wait_ms)).await;
-                }
-                Err(e) => {
-                    println!("[LOG] Launch with params failed for TG {}: {}", profile_num, e);
-                }
-            }
-        } else {
-            println!("File not found: {}", telegram_exe_path.display());
+#[tauri::command]
+async fn request_telegram_launch_cancel() -> Result<(), String> {
+    if let Ok(launches) = TELEGRAM_LAUNCH_CANCELS.lock() {
+        for cancel in launches.iter() {
+            cancel.store(true, Ordering::SeqCst);
         }
-        let _ = app.emit("telegram-launch-progress", LaunchProgressPayload {
-            batch_index: index + 1,
-            batch_total: profile_ids.len(),
-            profile: profile_num,
-        });
-
     }
+    Ok(())
+}
 
-    Ok(launched_pids)
+/// Catalog entries in the legacy `(key, {name, app_name, app_type, ref_link, mixed})` shape.
+#[tauri::command]
+async fn get_available_links(workspace: Option<String>) -> Result<Vec<(String, serde_json::Value)>, String> {
+    println!("[LOG] Loading available links from catalog");
+    let catalog = links::with_catalog(&workspace_catalog_path(workspace.as_deref())?, |catalog| Ok(catalog.entries.clone()))?;
+    Ok(catalog
+        .into_iter()
+        .map(|entry| {
+            let value = serde_json::json!({
+                "name": entry.name,
+                "app_name": entry.link.app_name,
+                "app_type": entry.link.app_type,
+                "ref_link": entry.link.ref_link,
+                "mixed": entry.link.mixed
+            });
+            (entry.key, value)
+        })
+        .collect())
 }
 
 #[tauri::command]
-async fn request_telegram_launch_cancel() -> Result<(), String> {
-    TELEGRAM_LAUNCH_CANCELLED.store(true, Ordering::SeqCst);
-    Ok(())
+async fn get_link_catalog(workspace: Option<String>) -> Result<Vec<links::LinkEntry>, String> {
+    links::with_catalog(&workspace_catalog_path(workspace.as_deref())?, |catalog| Ok(catalog.entries.clone()))
 }
 
 #[tauri::command]
-async fn get_available_links() -> Result<Vec<(String, serde_json::Value)>, String> {
-    println!("[LOG] Loading available links (static config)");
-
-    Ok(vec![
-        ("GetBonus".to_string(), serde_json::json!({
-            "name": "GetBonus",
-            "app_name": "getbonus",
-            "app_type": "app",
-            "ref_link": "",
-            "mixed": "yes"
-        })),
-        ("Lootly".to_string(), serde_json::json!({
-            "name": "Lootly",
-            "app_name": "LootlyGameBot",
-            "app_type": "start",
-            "ref_link": "",
-            "mixed": "yes"
-        })),
-        ("Twist".to_string(), serde_json::json!({
-            "name": "Twist",
-            "app_name": "twistappbot",
-            "app_type": "app",
-            "ref_link": "",
-            "mixed": "yes"
-        })),
-        ("Rolls".to_string(), serde_json::json!({
-            "name": "Rolls",
-            "app_name": "rollsgame_bot",
-            "app_type": "app",
-            "ref_link": "ref_xEmnaKUVPi",
-            "mixed": "yes"
-        })),
-        ("Qzino".to_string(), serde_json::json!({
-            "name": "Qzino",
-            "app_name": "qzino_official_bot",
-            "app_type": "app",
-            "ref_link": "",
-            "mixed": "yes"
-        })),
-        ("Quantum".to_string(), serde_json::json!({
-            "name": "Quantum Machines",
-            "app_name": "Quantum_Machines_bot",
-            "app_type": "start",
-            "ref_link": "",
-            "mixed": "yes"
-        })),
-        ("VIRUS".to_string(), serde_json::json!({
-            "name": "VIRUS",
-            "app_name": "virus_play_bot",
-            "app_type": "app",
-            "ref_link": "",
-            "mixed": "yes"
-        }))
-    ])
+async fn save_link_entry(entry: links::LinkEntryInput, workspace: Option<String>) -> Result<links::LinkEntry, String> {
+    build_telegram_link(entry.link.clone()).await?;
+    links::with_catalog(&workspace_catalog_path(workspace.as_deref())?, |catalog| catalog.upsert(entry))
+}
+
+#[tauri::command]
+async fn delete_link_entry(entry_id: String, workspace: Option<String>) -> Result<(), String> {
+    links::with_catalog(&workspace_catalog_path(workspace.as_deref())?, |catalog| {
+        let before = catalog.entries.len();
+        catalog.entries.retain(|entry| entry.id != entry_id);
+        if catalog.entries.len() == before {
+            return Err(format!("Link entry not found: {}", entry_id));
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+async fn reorder_link_entries(entry_ids: Vec<String>, workspace: Option<String>) -> Result<Vec<links::LinkEntry>, String> {
+    links::with_catalog(&workspace_catalog_path(workspace.as_deref())?, |catalog| {
+        catalog.reorder(&entry_ids)?;
+        Ok(catalog.entries.clone())
+    })
 }
+
+#[tauri::command]
+async fn export_link_catalog(workspace: Option<String>) -> Result<String, String> {
+    let entries = links::with_catalog(&workspace_catalog_path(workspace.as_deref())?, |catalog| Ok(catalog.entries.clone()))?;
+    serde_json::to_string_pretty(&entries).map_err(|e| format!("Failed to serialize link catalog: {}", e))
+}
+
+/// Imports entries exported by `export_link_catalog`. Entries with a known id are updated,
+/// the rest are appended; `replace` drops the current catalog first.
+#[tauri::command]
+async fn import_link_catalog(
+    content: String,
+    replace: Option<bool>,
+    workspace: Option<String>,
+) -> Result<Vec<links::LinkEntry>, String> {
+    let inputs: Vec<links::LinkEntryInput> =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse link catalog: {}", e))?;
+    for input in &inputs {
+        build_telegram_link(input.link.clone())
+            .await
+            .map_err(|e| format!("Invalid link \"{}\": {}", input.name, e))?;
+    }
+
+    links::with_catalog(&workspace_catalog_path(workspace.as_deref())?, |catalog| {
+        if replace.unwrap_or(false) {
+            catalog.entries.clear();
+        }
+        for input in inputs {
+            catalog.upsert(input)?;
+        }
+        Ok(catalog.entries.clone())
+    })
+}
+
 #[tauri::command]
 async fn build_telegram_link(link_params: TelegramLink) -> Result<String, String> {
     println!(
-        "[LOG] Link params: app_name={}, app_type={}, ref_link={}",
-        link_params.app_name, link_params.app_type, link_params.ref_link
+        "[LOG] Link params: kind={}, app_name={}, app_type={}, ref_link={}",
+        link_params.link_kind, link_params.app_name, link_params.app_type, link_params.ref_link
     );
 
-    let mut link = format!("tg://resolve?domain={}", link_params.app_name);
-    let app_type = link_params.app_type.trim();
-    let raw_ref_link = link_params.ref_link.trim();
-
-    if !app_type.is_empty() {
-        link += &format!("&appname={}", app_type);
-
-        // Extract startapp/start payload from a full URL when possible.
-        let payload = if raw_ref_link.is_empty() {
-            String::new()
-        } else if raw_ref_link.contains("startapp=") || raw_ref_link.contains("start=") {
-            if let Ok(url) = url::Url::parse(raw_ref_link) {
-                url.query_pairs()
-                    .find(|(key, _)| key == "startapp" || key == "start")
-                    .map(|(_, value)| value.to_string())
-                    .unwrap_or_else(|| {
-                        println!("[LOG] Failed to extract startapp/start, using ref_link as is");
-                        raw_ref_link.to_string()
-                    })
-            } else {
-                println!("[LOG] Failed to parse URL, using ref_link as is");
-                raw_ref_link.to_string()
-            }
-        } else {
-            raw_ref_link.to_string()
-        };
-
-        if !payload.is_empty() {
-            link += &format!("&startapp={}", payload);
-        } else {
-            println!("[LOG] app_type provided without payload, using appname only");
-        }
-    } else if !raw_ref_link.is_empty() {
-        link += &format!("&start={}", raw_ref_link);
-        println!("[LOG] Using start without app_type");
-    } else {
-        println!("[LOG] No app_type/ref_link payload, using domain-only resolve link");
+    let sequence = build_link_sequence(&templating::preview_link(&link_params)?)?;
+    for (step, planned) in sequence.iter().enumerate().skip(1) {
+        println!("[LOG] Link step {}: {}", step + 1, planned.link);
     }
-
+    let link = sequence[0].link.clone();
     println!("[LOG] Final link: {}", link);
     Ok(link)
 }
 
+/// Decodes a pasted `t.me`/`telegram.me`/`tg://` URL into link fields for the editor.
+#[tauri::command]
+async fn parse_telegram_link(url: String) -> Result<deeplink::ParsedTelegramLink, String> {
+    println!("[LOG] Parsing Telegram link: {}", url);
+    deeplink::parse(&url)
+}
+
+#[tauri::command]
+async fn get_app_directories() -> Result<paths::AppDirs, String> {
+    Ok(paths::dirs().clone())
+}
+
 #[tauri::command]
-async fn get_settings() -> Result<serde_json::Value, String> {
-    let settings = load_settings_from_disk();
+async fn get_settings(store: tauri::State<'_, Arc<settings::SettingsStore>>) -> Result<serde_json::Value, String> {
+    let settings = store.get();
     serde_json::to_value(settings).map_err(|e| format!("Failed to build settings response: {}", e))
 }
 
 #[tauri::command]
-async fn save_settings(settings: serde_json::Value) -> Result<(), String> {
-    let mut current = load_settings_from_disk();
+async fn save_settings(
+    app: tauri::AppHandle,
+    store: tauri::State<'_, Arc<settings::SettingsStore>>,
+    settings: serde_json::Value,
+) -> Result<(), String> {
+    let saved = store.update(|current| current.apply_patch(&settings))?;
+    let _ = app.emit("settings-changed", &saved);
+    Ok(())
+}
 
-    if let Some(v) = settings.get("telegramThreads").and_then(|v| v.as_str()) {
-        current.telegram_threads = v.to_string();
-    }
-    if let Some(v) = settings.get("telegramFolderPath").and_then(|v| v.as_str()) {
-        current.telegram_folder_path = v.to_string();
-    }
-    if let Some(v) = settings.get("telegramLaunchSpeed").and_then(|v| v.as_str()) {
-        current.telegram_launch_speed = v.to_string();
-    }
-    if let Some(v) = settings.get("chromeThreads").and_then(|v| v.as_str()) {
-        current.chrome_threads = v.to_string();
+#[tauri::command]
+async fn get_workspaces(
+    store: tauri::State<'_, Arc<settings::SettingsStore>>,
+) -> Result<Vec<settings::Workspace>, String> {
+    Ok(store.get().workspaces)
+}
+
+/// Creates a workspace (blank id) or replaces the one with the same id.
+#[tauri::command]
+async fn save_workspace(
+    app: tauri::AppHandle,
+    store: tauri::State<'_, Arc<settings::SettingsStore>>,
+    workspace: settings::Workspace,
+) -> Result<settings::Workspace, String> {
+    let mut saved_workspace = None;
+    let saved = store.update(|current| {
+        saved_workspace = Some(current.upsert_workspace(workspace)?);
+        Ok(())
+    })?;
+    let _ = app.emit("settings-changed", &saved);
+    saved_workspace.ok_or_else(|| "Failed to save workspace".to_string())
+}
+
+/// Removes a workspace together with its link catalog.
+#[tauri::command]
+async fn delete_workspace(
+    app: tauri::AppHandle,
+    store: tauri::State<'_, Arc<settings::SettingsStore>>,
+    workspace_id: String,
+) -> Result<(), String> {
+    let saved = store.update(|current| current.remove_workspace(&workspace_id).map(|_| ()))?;
+    // The default workspace's catalog lives outside its folder; remove it too, or a later
+    // workspace with the same id would inherit it.
+    let catalog = link_catalog_path(&workspace_id);
+    if catalog.is_file() {
+        if let Err(e) = fs::remove_file(&catalog) {
+            println!("[LOG] Failed to remove link catalog {}: {}", catalog.display(), e);
+        }
     }
-    if let Some(v) = settings.get("chromeFolderPath").and_then(|v| v.as_str()) {
-        current.chrome_folder_path = v.to_string();
+    let dir = workspace_dir(&workspace_id);
+    if dir.is_dir() {
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            println!("[LOG] Failed to remove workspace folder {}: {}", dir.display(), e);
+        }
     }
+    let _ = app.emit("settings-changed", &saved);
+    Ok(())
+}
 
-    save_settings_to_disk(&current)
+/// Switches the active workspace by id or name.
+#[tauri::command]
+async fn set_active_workspace(
+    app: tauri::AppHandle,
+    store: tauri::State<'_, Arc<settings::SettingsStore>>,
+    workspace: String,
+) -> Result<settings::Workspace, String> {
+    let saved = store.update(|current| {
+        current.active_workspace = current.workspace(Some(&workspace))?.id.clone();
+        Ok(())
+    })?;
+    let _ = app.emit("settings-changed", &saved);
+    Ok(saved.active().clone())
+}
+
+/// Lists every account folder under the Telegram root, including ones outside the folder
+/// layout; the returned ids are accepted by the launch and close commands.
+#[tauri::command]
+async fn discover_telegram_accounts(
+    telegram_folder_path: Option<String>,
+    workspace: Option<String>,
+) -> Result<Vec<layout::AccountIdentity>, String> {
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root = telegram_root_for(telegram_folder_path, &workspace);
+    if root.is_empty() {
+        return Err("Telegram folder path is not configured".to_string());
+    }
+    let root = PathBuf::from(root);
+    fs::read_dir(&root).map_err(|e| format!("Failed to read accounts directory: {}", e))?;
+    Ok(workspace.account_layout.discover(&root))
 }
 
+/// Classifies one account's `tdata` and lists the files behind the verdict.
 #[tauri::command]
-async fn get_account_stats(telegram_folder_path: Option<String>) -> Result<serde_json::Value, String> {
-    let settings = load_settings_from_disk();
-    let root = telegram_folder_path
+async fn inspect_telegram_account(
+    account_id: layout::AccountRef,
+    workspace: Option<String>,
+) -> Result<tdata::TdataReport, String> {
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root = workspace.telegram_folder_path.trim().to_string();
+    if root.is_empty() {
+        return Err("Telegram folder path is not configured".to_string());
+    }
+    let account = workspace.account_layout.resolve(Path::new(&root), &account_id)?;
+    Ok(tdata::inspect(&Path::new(&root).join(&account.folder).join("tdata")))
+}
+
+/// Explicit folder from the webview, else the workspace's Telegram folder.
+fn telegram_root_for(telegram_folder_path: Option<String>, workspace: &settings::Workspace) -> String {
+    telegram_folder_path
         .as_deref()
         .map(str::trim)
         .filter(|s| !s.is_empty())
         .map(str::to_string)
-        .unwrap_or_else(|| settings.telegram_folder_path.trim().to_string());
+        .unwrap_or_else(|| workspace.telegram_folder_path.trim().to_string())
+}
 
+/// Counts accounts as running (a matched process), blocked (tdata not authorized) or unknown.
+/// Answers from the background scan, but blocks for the first scan of a new root, so callers
+/// run it on a blocking thread; `detailed` adds one row per account folder.
+fn account_stats(root: String, layout: &layout::AccountLayout, detailed: bool) -> Result<serde_json::Value, String> {
     if root.is_empty() {
         return Ok(serde_json::json!({
             "total": 0,
@@ -1939,153 +2833,227 @@ async fn get_account_stats(telegram_folder_path: Option<String>) -> Result<serde
         }));
     }
 
-    let mut accounts: Vec<(String, PathBuf)> = Vec::new();
-    let entries = fs::read_dir(&root_path).map_err(|e| format!("Failed to read accounts directory: {}", e))?;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        let name = entry.file_name().to_string_lossy().to_string();
-        if !path.is_dir() {
-            continue;
-        }
-
-        let has_tdata = path.join("tdata").is_dir();
-        if has_tdata {
-            accounts.push((name, path));
-        }
+    // `scan` treats an unreadable root as empty, so surface that error here.
+    fs::read_dir(&root_path).map_err(|e| format!("Failed to read accounts directory: {}", e))?;
+    let (stats, rows) = if detailed {
+        let (stats, rows) = scanner::rows(&root_path, layout)?;
+        (stats, Some(rows))
+    } else {
+        (scanner::stats(&root_path, layout)?, None)
+    };
+    let mut value = serde_json::to_value(&stats).map_err(|e| format!("Failed to serialize account stats: {}", e))?;
+    value["telegramFolderPath"] = serde_json::Value::String(root);
+    if let Some(rows) = rows {
+        value["accounts"] = serde_json::to_value(&rows).map_err(|e| format!("Failed to serialize account stats: {}", e))?;
     }
+    Ok(value)
+}
 
-    let total = accounts.len() as i64;
-    if total == 0 {
-        return Ok(serde_json::json!({
-            "total": 0,
-            "running": 0,
-            "active": 0,
-            "blocked": 0,
-            "unknown": 0,
-            "telegramFolderPath": root
-        }));
+#[tauri::command]
+async fn get_account_stats(
+    telegram_folder_path: Option<String>,
+    workspace: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root = telegram_root_for(telegram_folder_path, &workspace);
+    tauri::async_runtime::spawn_blocking(move || account_stats(root, &workspace.account_layout, false))
+        .await
+        .map_err(|e| format!("Account scan failed: {}", e))?
+}
+
+/// `get_account_stats` plus an `accounts` array with one [`scanner::AccountStatusRow`] per folder.
+#[tauri::command]
+async fn get_account_stats_detailed(
+    telegram_folder_path: Option<String>,
+    workspace: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root = telegram_root_for(telegram_folder_path, &workspace);
+    tauri::async_runtime::spawn_blocking(move || account_stats(root, &workspace.account_layout, true))
+        .await
+        .map_err(|e| format!("Account scan failed: {}", e))?
+}
+
+#[tauri::command]
+async fn update_account_status(
+    account_id: String,
+    status: String,
+    workspace: Option<String>,
+) -> Result<(), String> {
+    let status = status.trim().to_lowercase();
+    if !accounts::ACCOUNT_STATUSES.contains(&status.as_str()) {
+        return Err(format!("Unknown account status: {}", status));
     }
 
-    let running_processes = list_running_telegram_processes();
-    let process_paths: Vec<String> = running_processes
-        .iter()
-        .map(|(_, _, path)| path.to_lowercase().replace('\\', "/"))
-        .collect();
-    let mut used_process_indexes: HashSet<usize> = HashSet::new();
-
-    let mut running: i64 = 0;
-    let mut blocked: i64 = 0;
-
-    for (name, account_path) in &accounts {
-        let account_path_lower = account_path
-            .to_string_lossy()
-            .to_lowercase()
-            .replace('\\', "/");
-        let account_name_lower = name.to_lowercase();
-        let account_name_segment = format!("/{}/", account_name_lower);
-
-        let mut matched_process_index: Option<usize> = None;
-        for (index, process_path) in process_paths.iter().enumerate() {
-            if used_process_indexes.contains(&index) {
-                continue;
-            }
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let key = accounts::normalize_account_key(&account_id, &workspace.id, &workspace.account_layout)?;
+    update_account_record(&workspace, &key, |account| account.status = status.clone())
+}
 
-            let path_matches = !account_path_lower.is_empty()
-                && (process_path == &account_path_lower
-                    || process_path.starts_with(&(account_path_lower.clone() + "/")));
-            let name_matches = !account_name_lower.is_empty()
-                && process_path.contains(&account_name_segment);
+#[tauri::command]
+async fn update_account_details(
+    account_id: String,
+    notes: Option<String>,
+    project: Option<String>,
+    workspace: Option<String>,
+) -> Result<AccountRecord, String> {
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let key = accounts::normalize_account_key(&account_id, &workspace.id, &workspace.account_layout)?;
+    update_account_record(&workspace, &key, |account| {
+        if let Some(notes) = notes {
+            account.notes = notes;
+        }
+        if let Some(project) = project {
+            account.project = project.trim().to_string();
+        }
+        account.clone()
+    })
+}
 
-            if path_matches || name_matches {
-                matched_process_index = Some(index);
-                break;
-            }
+#[tauri::command]
+async fn get_recent_actions(query: Option<journal::ActionQuery>) -> Result<journal::ActionPage, String> {
+    journal::query(&journal_dir(), &query.unwrap_or_default())
+}
+
+#[tauri::command]
+async fn get_daily_tasks() -> Result<Vec<tasks::DailyTask>, String> {
+    let now = chrono::Utc::now();
+    tasks::with_tasks(&daily_tasks_path(), |store| {
+        for task in store.tasks.iter_mut() {
+            task.refresh_completed(now);
         }
+        Ok(store.tasks.clone())
+    })
+}
 
-        if let Some(index) = matched_process_index {
-            used_process_indexes.insert(index);
-            running += 1;
-            continue;
+/// `None` for ids only the webview knows: its own localStorage tasks still call this to
+/// report completion, and the backend has nothing to update for them.
+#[tauri::command]
+async fn update_daily_task(task_id: String, completed: bool) -> Result<Option<tasks::DailyTask>, String> {
+    tasks::with_tasks(&daily_tasks_path(), |store| {
+        let Some(task) = store.find_mut(&task_id) else {
+            println!("[LOG] Daily task {} not found, nothing to update", task_id);
+            return Ok(None);
+        };
+        task.set_completed(completed, chrono::Utc::now());
+        Ok(Some(task.clone()))
+    })
+}
+
+#[tauri::command]
+async fn save_daily_task(task: tasks::DailyTaskInput) -> Result<tasks::DailyTask, String> {
+    let now = chrono::Utc::now();
+    tasks::with_tasks(&daily_tasks_path(), |store| {
+        if let Some(id) = task.id.clone().filter(|id| !id.trim().is_empty()) {
+            let existing = store
+                .find_mut(&id)
+                .ok_or_else(|| format!("Daily task not found: {}", id))?;
+            existing.apply_input(task, now)?;
+            return Ok(existing.clone());
         }
 
-        let tdata_path = account_path.join("tdata");
-        if is_likely_logged_out(&tdata_path) {
-            blocked += 1;
+        let created = tasks::DailyTask::from_input(task, now)?;
+        store.tasks.push(created.clone());
+        Ok(created)
+    })
+}
+
+#[tauri::command]
+async fn delete_daily_task(task_id: String) -> Result<(), String> {
+    tasks::with_tasks(&daily_tasks_path(), |store| {
+        let before = store.tasks.len();
+        store.tasks.retain(|task| task.id != task_id);
+        if store.tasks.len() == before {
+            return Err(format!("Daily task not found: {}", task_id));
         }
-    }
+        Ok(())
+    })
+}
 
-    let unknown = (total - running - blocked).max(0);
+#[tauri::command]
+async fn get_launch_jobs() -> Result<Vec<jobs::LaunchJob>, String> {
+    jobs::with_jobs(&launch_jobs_path(), |store| Ok(store.jobs.clone()))
+}
 
-    Ok(serde_json::json!({
-        "total": total,
-        "running": running,
-        "active": running,
-        "blocked": blocked,
-        "unknown": unknown,
-        "telegramFolderPath": root
-    }))
+#[tauri::command]
+async fn save_launch_job(
+    mut job: jobs::LaunchJobInput,
+    workspace: Option<String>,
+) -> Result<jobs::LaunchJob, String> {
+    let now = chrono::Utc::now();
+    // Jobs keep the workspace they were saved in, so switching workspaces does not retarget
+    // them. A new job without one takes the active workspace; an edit keeps its own.
+    let settings = current_settings();
+    job.workspace_id = match workspace.as_deref().map(str::trim).filter(|key| !key.is_empty()) {
+        Some(key) => Some(settings.workspace(Some(key))?.id.clone()),
+        None => None,
+    };
+    jobs::with_jobs(&launch_jobs_path(), |store| {
+        if let Some(id) = job.id.clone().filter(|id| !id.trim().is_empty()) {
+            let existing = store
+                .find_mut(&id)
+                .ok_or_else(|| format!("Launch job not found: {}", id))?;
+            existing.apply_input(job, now)?;
+            return Ok(existing.clone());
+        }
+
+        let mut created = jobs::LaunchJob::from_input(job, now)?;
+        if created.workspace_id.is_empty() {
+            created.workspace_id = settings.active().id.clone();
+        }
+        store.jobs.push(created.clone());
+        Ok(created)
+    })
 }
 
 #[tauri::command]
-async fn update_account_status(account_id: String, status: String) -> Result<(), String> {
-    // In a real app, you would update the database
-    println!("Updated account {} status to {}", account_id, status);
-    Ok(())
+async fn delete_launch_job(job_id: String) -> Result<(), String> {
+    jobs::with_jobs(&launch_jobs_path(), |store| {
+        let before = store.jobs.len();
+        store.jobs.retain(|job| job.id != job_id);
+        if store.jobs.len() == before {
+            return Err(format!("Launch job not found: {}", job_id));
+        }
+        Ok(())
+    })
 }
 
 #[tauri::command]
-async fn get_recent_actions() -> Result<Vec<serde_json::Value>, String> {
-    // Mock recent actions
-    let actions = vec![
-        serde_json::json!({
-            "id": "1",
-            "action": "launch",
-            "target": "Account 1",
-            "timestamp": "2024-02-09T10:30:00Z"
-        }),
-        serde_json::json!({
-            "id": "2",
-            "action": "launch",
-            "target": "Account 2",
-            "timestamp": "2024-02-09T10:31:00Z"
-        }),
-        serde_json::json!({
-            "id": "3",
-            "action": "launch",
-            "target": "Account 3",
-            "timestamp": "2024-02-09T10:32:00Z"
-        })
-    ];
-    Ok(actions)
+async fn run_launch_job_now(app: tauri::AppHandle, job_id: String) -> Result<bool, String> {
+    let job = jobs::with_jobs(&launch_jobs_path(), |store| {
+        let job = store
+            .find_mut(&job_id)
+            .ok_or_else(|| format!("Launch job not found: {}", job_id))?;
+        job.last_run_at = Some(chrono::Utc::now());
+        Ok(job.clone())
+    })?;
+    Ok(start_launch_job(app, job))
 }
 
+/// Stops a running job's Telegram launch; returns `false` when the job is not running.
 #[tauri::command]
-async fn get_daily_tasks() -> Result<Vec<serde_json::Value>, String> {
-    // Mock daily tasks
-    let tasks = vec![
-        serde_json::json!({
-            "id": "1",
-            "title": "Launch Accounts",
-            "description": "Launch Telegram accounts 1-10",
-            "status": "completed",
-            "due_date": "2024-02-09T10:00:00Z"
-        }),
-        serde_json::json!({
-            "id": "2",
-            "title": "Launch Accounts",
-            "description": "Launch Telegram accounts 11-20",
-            "status": "pending",
-            "due_date": "2024-02-09T11:00:00Z"
-        })
-    ];
-    Ok(tasks)
+async fn cancel_launch_job(job_id: String) -> Result<bool, String> {
+    let cancel = running_launch_jobs()
+        .lock()
+        .map_err(|_| "Launch job lock is poisoned".to_string())?
+        .get(&job_id)
+        .cloned();
+    Ok(cancel.map(|cancel| cancel.store(true, Ordering::SeqCst)).is_some())
 }
 
 #[tauri::command]
-async fn update_daily_task(task_id: String, completed: bool) -> Result<(), String> {
-    // In a real app, you would update the database
-    println!("Updated task {} completed to {}", task_id, completed);
-    Ok(())
+async fn get_launch_job_runs(job_id: Option<String>, limit: Option<usize>) -> Result<Vec<jobs::JobRun>, String> {
+    let limit = limit.unwrap_or(50);
+    jobs::with_jobs(&launch_jobs_path(), |store| {
+        Ok(store
+            .runs
+            .iter()
+            .rev()
+            .filter(|run| job_id.as_deref().map(|id| run.job_id == id).unwrap_or(true))
+            .take(limit)
+            .cloned()
+            .collect())
+    })
 }
 
 #[tauri::command]
@@ -2244,11 +3212,15 @@ async fn open_directory_dialog(app: tauri::AppHandle) -> Result<String, String>
 }
 
 #[tauri::command]
-async fn close_telegram_processes(pids: Vec<u32>) -> Result<String, String> {
+async fn close_telegram_processes(
+    app: tauri::AppHandle,
+    pids: Vec<u32>,
+    workspace: Option<String>,
+) -> Result<String, String> {
     use std::process::Command;
 
-    let settings = load_settings_from_disk();
-    let root_raw = settings.telegram_folder_path.trim().to_string();
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root_raw = workspace.telegram_folder_path.trim().to_string();
     if root_raw.is_empty() {
         return Err("Telegram folder path is not configured".to_string());
     }
@@ -2256,12 +3228,17 @@ async fn close_telegram_processes(pids: Vec<u32>) -> Result<String, String> {
 
     let running = list_running_telegram_processes();
     let mut allowed_pids: HashSet<u32> = HashSet::new();
+    let mut closing_paths: Vec<String> = Vec::new();
     for (pid, _name, path) in running {
         let path_norm = normalize_path_for_match(&path);
         if path_norm.starts_with(&root) {
             allowed_pids.insert(pid);
+            if pids.contains(&pid) {
+                closing_paths.push(path_norm);
+            }
         }
     }
+    watchdog::forget(&closing_paths);
 
     let mut closed_count = 0;
     
@@ -2323,36 +3300,46 @@ async fn close_telegram_processes(pids: Vec<u32>) -> Result<String, String> {
         }
     }
 
+    refresh_account_processes(&app).await;
     Ok(format!("Closed {} processes", closed_count))
 }
 
 #[tauri::command]
-async fn close_telegram_accounts_batch(account_ids: Vec<i32>) -> Result<String, String> {
+async fn close_telegram_accounts_batch(
+    app: tauri::AppHandle,
+    account_ids: Vec<layout::AccountRef>,
+    workspace: Option<String>,
+) -> Result<String, String> {
     use std::process::Command;
 
     if account_ids.is_empty() {
         return Ok("Closed 0 processes".to_string());
     }
 
-    let settings = load_settings_from_disk();
-    let root_raw = settings.telegram_folder_path.trim().to_string();
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root_raw = workspace.telegram_folder_path.trim().to_string();
     if root_raw.is_empty() {
         return Err("Telegram folder path is not configured".to_string());
     }
     let root = normalize_path_for_match(&root_raw);
 
-    let account_dirs = build_account_dirs(&account_ids, &root_raw, &root);
+    let accounts = resolve_accounts(&workspace.account_layout, &root_raw, &account_ids)?;
+    let account_dirs = build_account_dirs(&accounts, &root_raw, &root);
 
     if account_dirs.is_empty() {
         return Ok("Closed 0 processes".to_string());
     }
+    watchdog::forget(&account_dirs);
 
     let mut closed_pids: HashSet<u32> = HashSet::new();
+    let mut close_errors: HashMap<u32, String> = HashMap::new();
+    let mut all_closed = false;
     let mut attempts = 0;
 
     while attempts < 3 {
         let target_pids = get_pids_for_account_dirs(&account_dirs);
         if target_pids.is_empty() {
+            all_closed = true;
             break;
         }
 
@@ -2373,15 +3360,14 @@ async fn close_telegram_accounts_batch(account_ids: Vec<i32>) -> Result<String,
                                 println!("Telegram process {} terminated (batch)", pid);
                             }
                         } else {
-                            println!(
-                                "Failed to terminate process {}: {}",
-                                pid,
-                                String::from_utf8_lossy(&output.stderr)
-                            );
+                            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                            println!("Failed to terminate process {}: {}", pid, stderr);
+                            close_errors.insert(pid, stderr.trim().to_string());
                         }
                     }
                     Err(e) => {
                         println!("Error terminating process {}: {}", pid, e);
+                        close_errors.insert(pid, e.to_string());
                     }
                 }
             }
@@ -2399,15 +3385,14 @@ async fn close_telegram_accounts_batch(account_ids: Vec<i32>) -> Result<String,
                                 println!("Telegram process {} terminated (batch)", pid);
                             }
                         } else {
-                            println!(
-                                "Failed to terminate process {}: {}",
-                                pid,
-                                String::from_utf8_lossy(&output.stderr)
-                            );
+                            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                            println!("Failed to terminate process {}: {}", pid, stderr);
+                            close_errors.insert(pid, stderr.trim().to_string());
                         }
                     }
                     Err(e) => {
                         println!("Error terminating process {}: {}", pid, e);
+                        close_errors.insert(pid, e.to_string());
                     }
                 }
             }
@@ -2419,23 +3404,55 @@ async fn close_telegram_accounts_batch(account_ids: Vec<i32>) -> Result<String,
         }
     }
 
+    // One journal event per account, so filtering the journal by target finds it.
+    let still_running: Vec<String> = if all_closed {
+        Vec::new()
+    } else {
+        list_running_telegram_processes()
+            .into_iter()
+            .map(|(_, _, path)| normalize_path_for_match(&path))
+            .collect()
+    };
+    let mut error = "Some processes are still running after 3 attempts".to_string();
+    if !close_errors.is_empty() {
+        let details = close_errors
+            .iter()
+            .map(|(pid, message)| format!("PID {}: {}", pid, message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        error = format!("{} ({})", error, details);
+    }
+    for account in &accounts {
+        let dir = normalize_path_for_match(&Path::new(&root_raw).join(&account.folder).to_string_lossy());
+        if still_running.iter().any(|path| path_in_account_dir(path, &dir)) {
+            journal_action(ActionKind::TelegramClose, &account.folder, ActionOutcome::Failed, Some(error.clone()));
+        } else {
+            journal_action(ActionKind::TelegramClose, &account.folder, ActionOutcome::Success, None);
+        }
+    }
+
+    refresh_account_processes(&app).await;
     Ok(format!("Closed {} processes", closed_pids.len()))
 }
 
 #[tauri::command]
-async fn get_telegram_pids_for_accounts(account_ids: Vec<i32>) -> Result<Vec<u32>, String> {
+async fn get_telegram_pids_for_accounts(
+    account_ids: Vec<layout::AccountRef>,
+    workspace: Option<String>,
+) -> Result<Vec<u32>, String> {
     if account_ids.is_empty() {
         return Ok(Vec::new());
     }
 
-    let settings = load_settings_from_disk();
-    let root_raw = settings.telegram_folder_path.trim().to_string();
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root_raw = workspace.telegram_folder_path.trim().to_string();
     if root_raw.is_empty() {
         return Err("Telegram folder path is not configured".to_string());
     }
     let root = normalize_path_for_match(&root_raw);
 
-    let account_dirs = build_account_dirs(&account_ids, &root_raw, &root);
+    let accounts = resolve_accounts(&workspace.account_layout, &root_raw, &account_ids)?;
+    let account_dirs = build_account_dirs(&accounts, &root_raw, &root);
     if account_dirs.is_empty() {
         return Ok(Vec::new());
     }
@@ -2444,16 +3461,21 @@ async fn get_telegram_pids_for_accounts(account_ids: Vec<i32>) -> Result<Vec<u32
 }
 
 #[tauri::command]
-async fn close_single_account(account_id: i32) -> Result<String, String> {
+async fn close_single_account(
+    app: tauri::AppHandle,
+    account_id: layout::AccountRef,
+    workspace: Option<String>,
+) -> Result<String, String> {
     use std::process::Command;
 
-    let settings = load_settings_from_disk();
-    let root = settings.telegram_folder_path.trim().to_string();
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let root = workspace.telegram_folder_path.trim().to_string();
     if root.is_empty() {
         return Err("Telegram folder path is not configured".to_string());
     }
 
-    let account_dir = Path::new(&root).join(format!("TG {}", account_id));
+    let account = workspace.account_layout.resolve(Path::new(&root), &account_id)?;
+    let account_dir = Path::new(&root).join(&account.folder);
     let account_dir_lower = account_dir
         .to_string_lossy()
         .to_lowercase()
@@ -2462,6 +3484,7 @@ async fn close_single_account(account_id: i32) -> Result<String, String> {
     if account_dir_lower.is_empty() {
         return Err("Invalid account folder path".to_string());
     }
+    watchdog::forget(&[normalize_path_for_match(&account_dir.to_string_lossy())]);
 
     let processes = list_running_telegram_processes();
     let mut target_pids: Vec<u32> = Vec::new();
@@ -2474,10 +3497,15 @@ async fn close_single_account(account_id: i32) -> Result<String, String> {
     }
 
     if target_pids.is_empty() {
-        return Err("No running Telegram processes found for this account".to_string());
+        let error = "No running Telegram processes found for this account".to_string();
+        journal_action(ActionKind::TelegramClose, &account.folder, ActionOutcome::Skipped, Some(error.clone()));
+        // The account list may still show processes that exited on their own.
+        refresh_account_processes(&app).await;
+        return Err(error);
     }
 
     let mut closed_count = 0;
+    let mut failures: Vec<String> = Vec::new();
 
     for pid in target_pids {
         #[cfg(target_os = "windows")]
@@ -2495,15 +3523,14 @@ async fn close_single_account(account_id: i32) -> Result<String, String> {
                         closed_count += 1;
                         println!("Telegram process {} terminated", pid);
                     } else {
-                        println!(
-                            "Failed to terminate process {}: {}",
-                            pid,
-                            String::from_utf8_lossy(&output.stderr)
-                        );
+                        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                        println!("Failed to terminate process {}: {}", pid, message);
+                        failures.push(format!("PID {}: {}", pid, message));
                     }
                 }
                 Err(e) => {
                     println!("Error terminating process {}: {}", pid, e);
+                    failures.push(format!("PID {}: {}", pid, e));
                 }
             }
         }
@@ -2520,22 +3547,30 @@ async fn close_single_account(account_id: i32) -> Result<String, String> {
                         closed_count += 1;
                         println!("Telegram process {} terminated", pid);
                     } else {
-                        println!(
-                            "Failed to terminate process {}: {}",
-                            pid,
-                            String::from_utf8_lossy(&output.stderr)
-                        );
+                        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                        println!("Failed to terminate process {}: {}", pid, message);
+                        failures.push(format!("PID {}: {}", pid, message));
                     }
                 }
                 Err(e) => {
                     println!("Error terminating process {}: {}", pid, e);
+                    failures.push(format!("PID {}: {}", pid, e));
                 }
             }
         }
     }
 
+    if failures.is_empty() {
+        journal_action(ActionKind::TelegramClose, &account.folder, ActionOutcome::Success, None);
+    } else {
+        let error = format!("Failed to close: {}", failures.join("; "));
+        journal_action(ActionKind::TelegramClose, &account.folder, ActionOutcome::Failed, Some(error));
+    }
+
+    refresh_account_processes(&app).await;
     Ok(format!("Closed {} Telegram processes", closed_count))
 }
+
 #[tauri::command]
 async fn get_running_telegram_processes() -> Result<Vec<serde_json::Value>, String> {
     let processes = list_running_telegram_processes()
@@ -2552,16 +3587,142 @@ async fn get_running_telegram_processes() -> Result<Vec<serde_json::Value>, Stri
     Ok(processes)
 }
 
+/// Spawns one Chrome profile window, waits `post_spawn_wait_ms` and, on Windows, then binds
+/// its HWND for a later targeted close.
+async fn spawn_chrome_profile(
+    workspace_id: &str,
+    chrome_exe: &Path,
+    user_data_dir: &Path,
+    profile_name: &str,
+    url: Option<&str>,
+    post_spawn_wait_ms: u64,
+) -> launcher::ProfileReport {
+    use std::process::Command;
+
+    let started = std::time::Instant::now();
+    let mut report = chrome_profile_report(chrome_exe, profile_name);
+
+    #[cfg(windows)]
+    let before_hwnds: HashSet<isize> = list_visible_chrome_window_handles().into_iter().collect();
+
+    let mut args = vec![
+        format!("--user-data-dir={}", user_data_dir.to_string_lossy()),
+        format!("--profile-directory={profile_name}"),
+    ];
+    if let Some(target) = url {
+        args.push(target.to_string());
+    }
+
+    match Command::new(chrome_exe).args(args).spawn() {
+        Ok(child) => {
+            report.pid = Some(child.id());
+            record_account_activity(workspace_id, AccountKind::Chrome, profile_name);
+            journal_action(ActionKind::ChromeLaunch, profile_name, ActionOutcome::Success, None);
+            if post_spawn_wait_ms > 0 {
+                tokio::time::sleep(tokio::time::Duration::from_millis(post_spawn_wait_ms)).await;
+            }
+            #[cfg(windows)]
+            {
+                let mut cached = false;
+                for _ in 0..12 {
+                    let after = list_visible_chrome_window_handles();
+                    if let Some(hwnd) = after.into_iter().find(|hwnd| !before_hwnds.contains(hwnd)) {
+                        cache_profile_hwnd(profile_name, hwnd);
+                        cached = true;
+                        break;
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+                }
+                if !cached {
+                    cache_chrome_window_for_profile(profile_name);
+                }
+            }
+        }
+        Err(e) => {
+            journal_action(
+                ActionKind::ChromeLaunch,
+                profile_name,
+                ActionOutcome::Failed,
+                Some(e.to_string()),
+            );
+            report = report.failed(format!("Failed to launch Chrome profile: {e}"));
+        }
+    }
+
+    report.elapsed_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+/// Hands `url` to the already running profile; Chrome opens it as a new tab.
+fn open_chrome_url(chrome_exe: &Path, user_data_dir: &Path, profile_name: &str, url: &str) -> Result<(), String> {
+    std::process::Command::new(chrome_exe)
+        .arg(format!("--user-data-dir={}", user_data_dir.to_string_lossy()))
+        .arg(format!("--profile-directory={profile_name}"))
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {} in {}: {}", url, profile_name, e))
+}
+
+fn chrome_profile_report(chrome_exe: &Path, profile_name: &str) -> launcher::ProfileReport {
+    let number = profile_name
+        .strip_prefix("Profile ")
+        .and_then(|raw| raw.parse::<i32>().ok())
+        .unwrap_or(0);
+    launcher::ProfileReport::new(profile_name.to_string(), number, chrome_exe)
+}
+
+fn skipped_chrome_profile_report(chrome_exe: &Path, profile_name: &str) -> launcher::ProfileReport {
+    let mut report = chrome_profile_report(chrome_exe, profile_name);
+    report.status = launcher::ProfileStatus::Skipped;
+    report
+}
+
+/// Starts one profile with the workspace's Chrome pacing: the spawn delay (plus jitter) is
+/// waited before the window is looked up, and with a link delay the URL is opened by a second
+/// command once the profile window is up.
+async fn spawn_chrome_profile_paced(
+    workspace: &settings::Workspace,
+    chrome_exe: &Path,
+    user_data_dir: &Path,
+    profile_name: &str,
+    url: Option<&str>,
+) -> launcher::ProfileReport {
+    let timing = workspace.chrome_timing_profile();
+    let separate_url = timing.link_delay_ms > 0;
+    let spawn_url = if separate_url { None } else { url };
+    let post_spawn_wait_ms = timing.jittered(timing.spawn_delay_ms);
+    let mut report =
+        spawn_chrome_profile(&workspace.id, chrome_exe, user_data_dir, profile_name, spawn_url, post_spawn_wait_ms)
+            .await;
+    if report.status != launcher::ProfileStatus::Launched {
+        return report;
+    }
+    if let Some(target) = url.filter(|_| separate_url) {
+        let delay = timing.jittered(timing.link_delay_ms);
+        tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+        match open_chrome_url(chrome_exe, user_data_dir, profile_name, target) {
+            Ok(()) => report.deep_link = launcher::DeepLinkStatus::Sent,
+            Err(e) => {
+                println!("[LOG] {}", e);
+                report.deep_link = launcher::DeepLinkStatus::Failed;
+                report.error = Some(e);
+            }
+        }
+    }
+    report
+}
+
 #[tauri::command]
 async fn launch_chrome_profiles(
+    app: tauri::AppHandle,
     chrome_folder_path: String,
     start_range: i32,
     end_range: i32,
     mixed: bool,
     target_url: Option<String>,
+    workspace: Option<String>,
 ) -> Result<ChromeLaunchResult, String> {
-    use std::process::Command;
-
     if start_range <= 0 || end_range <= 0 || end_range < start_range {
         return Err("Invalid range for Chrome profiles".to_string());
     }
@@ -2569,6 +3730,15 @@ async fn launch_chrome_profiles(
     let chrome_exe = resolve_chrome_exe()
         .ok_or_else(|| "Chrome executable was not found in Program Files".to_string())?;
 
+    // A blank folder falls back to the workspace's Chrome folder, then to Chrome's own default.
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let timing = workspace.chrome_timing_profile();
+    let batch_size = workspace.chrome_threads.max(1) as usize;
+    let chrome_folder_path = if chrome_folder_path.trim().is_empty() {
+        workspace.chrome_folder_path.clone()
+    } else {
+        chrome_folder_path
+    };
     let user_data_dir = if chrome_folder_path.trim().is_empty() {
         default_chrome_user_data_dir()
             .ok_or_else(|| "Cannot resolve Chrome User Data directory".to_string())?
@@ -2624,62 +3794,42 @@ async fn launch_chrome_profiles(
         }
     }
 
-    let mut started = 0usize;
-    let mut skipped = 0usize;
-    let mut failed = 0usize;
+    let started_at = chrono::Utc::now();
     let url = target_url
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty());
 
+    let mut reports = Vec::with_capacity(profiles.len());
+    let mut launched = 0usize;
     for (_num, profile_name) in &profiles {
         if opened_profiles.contains(profile_name) {
-            skipped += 1;
+            journal_action(ActionKind::ChromeLaunch, profile_name, ActionOutcome::Skipped, None);
+            reports.push(skipped_chrome_profile_report(&chrome_exe, profile_name));
             continue;
         }
-        #[cfg(windows)]
-        let before_hwnds: HashSet<isize> = list_visible_chrome_window_handles().into_iter().collect();
 
-        let mut args = vec![
-            format!("--user-data-dir={}", user_data_dir.to_string_lossy()),
-            format!("--profile-directory={profile_name}"),
-        ];
-        if let Some(target) = &url {
-            args.push(target.clone());
+        if let Some(cooldown_ms) = timing.cooldown_before(launched, batch_size) {
+            println!("[LOG] Chrome batch cooldown: waiting {} ms", cooldown_ms);
+            tokio::time::sleep(tokio::time::Duration::from_millis(cooldown_ms)).await;
         }
 
-        match Command::new(&chrome_exe).args(args).spawn() {
-            Ok(_) => {
-                started += 1;
-                opened_profiles.insert(profile_name.clone());
-                tokio::time::sleep(tokio::time::Duration::from_millis(600)).await;
-                #[cfg(windows)]
-                {
-                    let mut cached = false;
-                    for _ in 0..12 {
-                        let after = list_visible_chrome_window_handles();
-                        if let Some(hwnd) = after.into_iter().find(|hwnd| !before_hwnds.contains(hwnd)) {
-                            cache_profile_hwnd(profile_name.as_str(), hwnd);
-                            cached = true;
-                            break;
-                        }
-                        tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
-                    }
-                    if !cached {
-                        cache_chrome_window_for_profile(profile_name.as_str());
-                    }
-                }
-            }
-            Err(_) => {
-                failed += 1;
-            }
+        let report =
+            spawn_chrome_profile_paced(&workspace, &chrome_exe, &user_data_dir, profile_name, url.as_deref()).await;
+        if report.status == launcher::ProfileStatus::Launched {
+            launched += 1;
+            opened_profiles.insert(profile_name.clone());
         }
+        reports.push(report);
     }
 
+    let report = launcher::LaunchReport::from_profiles(started_at, profiles.len(), reports);
+    let _ = app.emit("chrome-launch-report", &report);
     Ok(ChromeLaunchResult {
         selected: profiles.len(),
-        started,
-        skipped,
-        failed,
+        started: report.launched,
+        skipped: report.skipped,
+        failed: report.failed,
+        report: Some(report),
     })
 }
 
@@ -2693,7 +3843,7 @@ async fn close_chrome_profiles(chrome_folder_path: String) -> Result<ChromeClose
     };
 
     let user_data_norm = normalize_path_for_match(&user_data_dir.to_string_lossy());
-    let mut system = System::new_all();
+    let mut system = System::new();
     system.refresh_processes();
 
     let target_pids: Vec<u32> = system
@@ -2884,12 +4034,12 @@ async fn get_closable_chrome_profiles(
 
 #[tauri::command]
 async fn launch_single_chrome_profile(
+    app: tauri::AppHandle,
     chrome_folder_path: String,
     profile_name: String,
     target_url: Option<String>,
-) -> Result<bool, String> {
-    use std::process::Command;
-
+    workspace: Option<String>,
+) -> Result<launcher::LaunchReport, String> {
     let profile = profile_name.trim().to_string();
     if profile.is_empty() {
         return Err("Profile name is required".to_string());
@@ -2908,6 +4058,7 @@ async fn launch_single_chrome_profile(
         return Err(format!("Profile directory not found: {}", profile_dir.to_string_lossy()));
     }
 
+    let started_at = chrono::Utc::now();
     let user_data_norm = normalize_user_data_dir_for_scope(&user_data_dir.to_string_lossy());
     for (_pid, _name, _path, cmd) in list_running_chrome_processes() {
         if !cmd_matches_user_data_scope(&cmd, user_data_norm.as_str()) {
@@ -2917,48 +4068,25 @@ async fn launch_single_chrome_profile(
             parse_profile_directory_arg(&cmd).or_else(|| parse_profile_from_user_data_dir_arg(&cmd))
         {
             if cmd_profile == profile {
-                return Ok(false);
+                let report = skipped_chrome_profile_report(&chrome_exe, &profile);
+                let report = launcher::LaunchReport::from_profiles(started_at, 1, vec![report]);
+                let _ = app.emit("chrome-launch-report", &report);
+                return Ok(report);
             }
         }
     }
 
-    let mut args = vec![
-        format!("--user-data-dir={}", user_data_dir.to_string_lossy()),
-        format!("--profile-directory={profile}"),
-    ];
-    if let Some(url) = target_url {
-        let trimmed = url.trim();
-        if !trimmed.is_empty() {
-            args.push(trimmed.to_string());
-        }
-    }
-
-    #[cfg(windows)]
-    let before_hwnds: HashSet<isize> = list_visible_chrome_window_handles().into_iter().collect();
-
-    Command::new(chrome_exe)
-        .args(args)
-        .spawn()
-        .map_err(|e| format!("Failed to launch Chrome profile: {e}"))?;
-
-    #[cfg(windows)]
-    {
-        let mut cached = false;
-        for _ in 0..12 {
-            let after = list_visible_chrome_window_handles();
-            if let Some(hwnd) = after.into_iter().find(|hwnd| !before_hwnds.contains(hwnd)) {
-                cache_profile_hwnd(profile.as_str(), hwnd);
-                cached = true;
-                break;
-            }
-            tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
-        }
-        if !cached {
-            cache_chrome_window_for_profile(profile.as_str());
-        }
-    }
-
-    Ok(true)
+    let url = target_url
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    // The Chrome page opens its batches one profile at a time, so each call is paced like a
+    // step of `launch_chrome_profiles`; the page's Continue button takes the place of the
+    // batch cooldown.
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    let report = spawn_chrome_profile_paced(&workspace, &chrome_exe, &user_data_dir, &profile, url.as_deref()).await;
+    let report = launcher::LaunchReport::from_profiles(started_at, 1, vec![report]);
+    let _ = app.emit("chrome-launch-report", &report);
+    Ok(report)
 }
 
 #[tauri::command]
@@ -2971,6 +4099,21 @@ async fn close_single_chrome_profile(
         return Err("Profile name is required".to_string());
     }
 
+    let result = close_chrome_profile_by_name(&chrome_folder_path, &profile);
+    match &result {
+        Ok(true) => journal_action(ActionKind::ChromeClose, &profile, ActionOutcome::Success, None),
+        Ok(false) => journal_action(
+            ActionKind::ChromeClose,
+            &profile,
+            ActionOutcome::Failed,
+            Some("No closable window or process found for this profile".to_string()),
+        ),
+        Err(e) => journal_action(ActionKind::ChromeClose, &profile, ActionOutcome::Failed, Some(e.clone())),
+    }
+    result
+}
+
+fn close_chrome_profile_by_name(chrome_folder_path: &str, profile: &str) -> Result<bool, String> {
     #[cfg(windows)]
     {
         let user_data_dir = if chrome_folder_path.trim().is_empty() {
@@ -2980,18 +4123,18 @@ async fn close_single_chrome_profile(
             PathBuf::from(chrome_folder_path.trim())
         };
 
-        if close_chrome_profile_windows(profile.as_str()).unwrap_or(false) {
+        if close_chrome_profile_windows(profile).unwrap_or(false) {
             return Ok(true);
         }
 
-        if try_infer_and_close_profile_by_single_unmapped_window(profile.as_str(), &user_data_dir)
+        if try_infer_and_close_profile_by_single_unmapped_window(profile, &user_data_dir)
             .unwrap_or(false)
         {
             return Ok(true);
         }
 
         let mut closed_any = false;
-        if let Some(pids) = find_chrome_pids_by_profile_windows(profile.as_str()) {
+        if let Some(pids) = find_chrome_pids_by_profile_windows(profile) {
             for pid in pids {
                 if kill_pid_windows_force(pid) {
                     closed_any = true;
@@ -3003,11 +4146,11 @@ async fn close_single_chrome_profile(
 
     #[cfg(not(windows))]
     {
-        let user_data_dir = if _chrome_folder_path.trim().is_empty() {
+        let user_data_dir = if chrome_folder_path.trim().is_empty() {
             default_chrome_user_data_dir()
                 .ok_or_else(|| "Cannot resolve Chrome User Data directory".to_string())?
         } else {
-            PathBuf::from(_chrome_folder_path.trim())
+            PathBuf::from(chrome_folder_path.trim())
         };
         let user_data_norm = normalize_user_data_dir_for_scope(&user_data_dir.to_string_lossy());
 
@@ -3019,7 +4162,7 @@ async fn close_single_chrome_profile(
                 }
                 let cmd_profile =
                     parse_profile_directory_arg(&cmd).or_else(|| parse_profile_from_user_data_dir_arg(&cmd));
-                if cmd_profile.as_deref() != Some(profile.as_str()) {
+                if cmd_profile.as_deref() != Some(profile) {
                     return None;
                 }
                 Some(pid)
@@ -3040,7 +4183,7 @@ async fn close_single_chrome_profile(
             }
         }
 
-        return Ok(closed_any);
+        Ok(closed_any)
     }
 }
 
@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store;
+use crate::TelegramLink;
+
+static CATALOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// One campaign in the link catalog. The link fields are flattened so an entry can be
+/// handed to `build_telegram_link` or a launch command as-is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkEntry {
+    pub id: String,
+    /// Stable short key shown in selectors (`GetBonus`, `Quantum`, ...).
+    pub key: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub link: TelegramLink,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkEntryInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub key: Option<String>,
+    pub name: String,
+    #[serde(flatten)]
+    pub link: TelegramLink,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LinkCatalog {
+    /// Set once the built-in campaigns were written, so deleting them sticks.
+    #[serde(default)]
+    pub seeded: bool,
+    #[serde(default)]
+    pub entries: Vec<LinkEntry>,
+}
+
+fn builtin(key: &str, name: &str, app_name: &str, app_type: &str, ref_link: &str) -> LinkEntry {
+    LinkEntry {
+        id: format!("builtin-{}", key.to_lowercase()),
+        key: key.to_string(),
+        name: name.to_string(),
+        link: TelegramLink {
+            app_name: app_name.to_string(),
+            app_type: app_type.to_string(),
+            ref_link: ref_link.to_string(),
+            mixed: "yes".to_string(),
+            ..TelegramLink::default()
+        },
+        builtin: true,
+    }
+}
+
+/// Campaigns shipped with the app; only used to seed an empty catalog.
+pub fn seed_entries() -> Vec<LinkEntry> {
+    vec![
+        builtin("GetBonus", "GetBonus", "getbonus", "app", ""),
+        builtin("Lootly", "Lootly", "LootlyGameBot", "start", ""),
+        builtin("Twist", "Twist", "twistappbot", "app", ""),
+        builtin("Rolls", "Rolls", "rollsgame_bot", "app", "ref_xEmnaKUVPi"),
+        builtin("Qzino", "Qzino", "qzino_official_bot", "app", ""),
+        builtin("Quantum", "Quantum Machines", "Quantum_Machines_bot", "start", ""),
+        builtin("VIRUS", "VIRUS", "virus_play_bot", "app", ""),
+    ]
+}
+
+impl LinkEntry {
+    pub fn from_input(input: LinkEntryInput) -> Result<Self, String> {
+        let mut entry = LinkEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            key: String::new(),
+            name: String::new(),
+            link: TelegramLink::default(),
+            builtin: false,
+        };
+        entry.apply_input(input)?;
+        Ok(entry)
+    }
+
+    pub fn apply_input(&mut self, input: LinkEntryInput) -> Result<(), String> {
+        let name = input.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Link name is required".to_string());
+        }
+        self.key = input
+            .key
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+            .unwrap_or_else(|| name.clone());
+        self.name = name;
+        self.link = input.link;
+        Ok(())
+    }
+}
+
+impl LinkCatalog {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut catalog: LinkCatalog = store::load_json(path, "link catalog")?;
+        if !catalog.seeded {
+            catalog.entries.extend(seed_entries());
+            catalog.seeded = true;
+        }
+        Ok(catalog)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        store::save_json(path, self, "link catalog")
+    }
+
+    /// Creates or updates an entry; the input must already have passed `build_telegram_link`.
+    /// Keys are unique across the catalog, compared case-insensitively.
+    pub fn upsert(&mut self, input: LinkEntryInput) -> Result<LinkEntry, String> {
+        let existing = input
+            .id
+            .as_deref()
+            .and_then(|id| self.entries.iter().position(|entry| entry.id == id));
+        let entry = match existing {
+            Some(index) => {
+                let mut entry = self.entries[index].clone();
+                entry.apply_input(input)?;
+                entry
+            }
+            None => {
+                let mut entry = LinkEntry::from_input(input.clone())?;
+                if let Some(id) = input.id.filter(|id| !id.trim().is_empty()) {
+                    entry.id = id;
+                }
+                entry
+            }
+        };
+        if let Some(clash) = self
+            .entries
+            .iter()
+            .find(|other| other.id != entry.id && other.key.eq_ignore_ascii_case(&entry.key))
+        {
+            return Err(format!("Link key \"{}\" is already used by {}", entry.key, clash.name));
+        }
+        match existing {
+            Some(index) => self.entries[index] = entry.clone(),
+            None => self.entries.push(entry.clone()),
+        }
+        Ok(entry)
+    }
+
+    /// Moves the listed entries to the front in the given order; unlisted ones keep their
+    /// relative order after them.
+    pub fn reorder(&mut self, ids: &[String]) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        if let Some(repeated) = ids.iter().find(|id| !seen.insert(id.as_str())) {
+            return Err(format!("Link entry listed more than once: {}", repeated));
+        }
+        if let Some(unknown) = ids.iter().find(|id| !self.entries.iter().any(|entry| &entry.id == *id)) {
+            return Err(format!("Link entry not found: {}", unknown));
+        }
+        let mut ordered: Vec<LinkEntry> = ids
+            .iter()
+            .filter_map(|id| self.entries.iter().find(|entry| &entry.id == id).cloned())
+            .collect();
+        ordered.extend(self.entries.iter().filter(|entry| !ids.contains(&entry.id)).cloned());
+        self.entries = ordered;
+        Ok(())
+    }
+}
+
+/// Runs a load/mutate/save cycle on the catalog file under a process-wide lock.
+pub fn with_catalog<T>(
+    path: &Path,
+    mutate: impl FnOnce(&mut LinkCatalog) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = CATALOG_LOCK
+        .lock()
+        .map_err(|_| "Link catalog lock is poisoned".to_string())?;
+    let mut catalog = LinkCatalog::load(path)?;
+    let result = mutate(&mut catalog)?;
+    catalog.save(path)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(id: Option<&str>, key: &str, name: &str) -> LinkEntryInput {
+        LinkEntryInput {
+            id: id.map(str::to_string),
+            key: Some(key.to_string()),
+            name: name.to_string(),
+            link: TelegramLink::default(),
+        }
+    }
+
+    fn catalog() -> LinkCatalog {
+        LinkCatalog {
+            seeded: true,
+            entries: seed_entries(),
+        }
+    }
+
+    fn ids(catalog: &LinkCatalog) -> Vec<&str> {
+        catalog.entries.iter().map(|entry| entry.id.as_str()).collect()
+    }
+
+    #[test]
+    fn reorder_moves_listed_entries_first() {
+        let mut catalog = catalog();
+        catalog
+            .reorder(&["builtin-virus".to_string(), "builtin-lootly".to_string()])
+            .unwrap();
+        assert_eq!(&ids(&catalog)[..3], ["builtin-virus", "builtin-lootly", "builtin-getbonus"]);
+        assert_eq!(catalog.entries.len(), seed_entries().len());
+    }
+
+    #[test]
+    fn reorder_rejects_repeated_and_unknown_ids() {
+        let mut catalog = catalog();
+        let before: Vec<String> = ids(&catalog).iter().map(|id| id.to_string()).collect();
+        assert!(catalog
+            .reorder(&["builtin-virus".to_string(), "builtin-virus".to_string()])
+            .is_err());
+        assert!(catalog.reorder(&["missing".to_string()]).is_err());
+        assert_eq!(ids(&catalog), before);
+    }
+
+    #[test]
+    fn upsert_keeps_keys_unique() {
+        let mut catalog = catalog();
+        assert!(catalog.upsert(input(None, "rolls", "Another Rolls")).is_err());
+        assert!(catalog.upsert(input(Some("builtin-twist"), "Rolls", "Twist")).is_err());
+        assert_eq!(catalog.entries.len(), seed_entries().len());
+
+        let renamed = catalog.upsert(input(Some("builtin-rolls"), "Rolls", "Rolls v2")).unwrap();
+        assert_eq!(renamed.name, "Rolls v2");
+        let added = catalog.upsert(input(None, "Fresh", "Fresh")).unwrap();
+        assert_eq!(catalog.entries.last().map(|entry| entry.id.clone()), Some(added.id));
+    }
+}
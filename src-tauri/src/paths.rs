@@ -0,0 +1,293 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+/// Relocates config, data and cache into one directory, e.g. for a portable install.
+pub const CONFIG_DIR_ENV: &str = "ABUSEAPP_CONFIG_DIR";
+const CONFIG_DIR_FLAG: &str = "--config-dir";
+
+/// Settings file name used when no `APPDATA` was set and everything lived in the working directory.
+const LEGACY_CWD_SETTINGS: &str = ".abuseapp-settings.json";
+/// Data files and folders that were written next to the legacy settings file.
+const LEGACY_CWD_DATA: [&str; 6] = [
+    "accounts.json",
+    "daily-tasks.json",
+    "launch-jobs.json",
+    "links.json",
+    "launch-sessions",
+    "journal",
+];
+
+static APP_DIRS: OnceLock<AppDirs> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDirs {
+    /// Holds `settings.json`.
+    pub config: PathBuf,
+    /// Registries, journals, sessions and other state the app owns.
+    pub data: PathBuf,
+    /// Set when the location came from `--config-dir` or `ABUSEAPP_CONFIG_DIR`.
+    pub overridden: bool,
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+#[cfg(not(windows))]
+fn home_dir() -> Option<PathBuf> {
+    env_dir("HOME").or_else(|| env_dir("USERPROFILE"))
+}
+
+fn cwd() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// `--config-dir <dir>` or `--config-dir=<dir>`; relative paths resolve against the working directory.
+fn config_dir_from_args(args: &[String]) -> Option<PathBuf> {
+    let raw = args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == CONFIG_DIR_FLAG {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(CONFIG_DIR_FLAG)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(str::to_string)
+        }
+    })?;
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    Some(cwd().join(raw))
+}
+
+fn override_dir(args: &[String]) -> Option<PathBuf> {
+    config_dir_from_args(args).or_else(|| {
+        std::env::var_os(CONFIG_DIR_ENV)
+            .filter(|value| !value.is_empty())
+            .map(|value| cwd().join(value))
+    })
+}
+
+#[cfg(windows)]
+fn platform_dirs() -> Option<AppDirs> {
+    let roaming = env_dir("APPDATA")?.join("AbuseApp");
+    Some(AppDirs {
+        config: roaming.clone(),
+        data: roaming,
+        overridden: false,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn platform_dirs() -> Option<AppDirs> {
+    let support = home_dir()?
+        .join("Library")
+        .join("Application Support")
+        .join("AbuseApp");
+    Some(AppDirs {
+        config: support.clone(),
+        data: support,
+        overridden: false,
+    })
+}
+
+/// XDG base directories, falling back to `~/.config` and `~/.local/share`.
+#[cfg(not(any(windows, target_os = "macos")))]
+fn platform_dirs() -> Option<AppDirs> {
+    let home = home_dir();
+    let xdg = |var: &str, fallback: &[&str]| {
+        env_dir(var)
+            .or_else(|| home.as_ref().map(|home| fallback.iter().fold(home.clone(), |dir, part| dir.join(part))))
+            .map(|dir| dir.join("abuseapp"))
+    };
+    Some(AppDirs {
+        config: xdg("XDG_CONFIG_HOME", &[".config"])?,
+        data: xdg("XDG_DATA_HOME", &[".local", "share"])?,
+        overridden: false,
+    })
+}
+
+fn resolve(args: &[String]) -> AppDirs {
+    if let Some(dir) = override_dir(args) {
+        return AppDirs {
+            config: dir.clone(),
+            data: dir,
+            overridden: true,
+        };
+    }
+    platform_dirs().unwrap_or_else(|| {
+        // No home directory at all: keep the old working-directory behaviour.
+        let dir = cwd();
+        AppDirs {
+            config: dir.clone(),
+            data: dir,
+            overridden: false,
+        }
+    })
+}
+
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    // Different volume: copy, then remove the original.
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            move_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        fs::remove_dir(from)
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)
+    }
+}
+
+/// Moves files left in the working directory (`legacy_dir`) by older builds into the resolved
+/// directories. Data files are only taken when the legacy settings file proves the directory
+/// was ours, and nothing that already exists at the destination is overwritten.
+fn migrate_legacy_cwd_files(legacy_dir: &Path, dirs: &AppDirs) {
+    let legacy_settings = legacy_dir.join(LEGACY_CWD_SETTINGS);
+    if !legacy_settings.is_file() {
+        return;
+    }
+
+    let mut moves = vec![(legacy_settings, dirs.config.join("settings.json"))];
+    if legacy_dir != dirs.data {
+        moves.extend(
+            LEGACY_CWD_DATA
+                .iter()
+                .map(|name| (legacy_dir.join(name), dirs.data.join(name))),
+        );
+    }
+    for (from, to) in moves {
+        if !from.exists() || to.exists() {
+            continue;
+        }
+        match move_path(&from, &to) {
+            Ok(()) => println!("[LOG] Migrated {} to {}", from.display(), to.display()),
+            Err(e) => println!("[LOG] Failed to migrate {}: {}", from.display(), e),
+        }
+    }
+}
+
+/// Resolves the app directories once per process and migrates legacy files into them.
+pub fn init(args: &[String]) -> &'static AppDirs {
+    APP_DIRS.get_or_init(|| {
+        let dirs = resolve(args);
+        println!(
+            "[LOG] App directories: config={}, data={}",
+            dirs.config.display(),
+            dirs.data.display()
+        );
+        migrate_legacy_cwd_files(&cwd(), &dirs);
+        dirs
+    })
+}
+
+pub fn dirs() -> &'static AppDirs {
+    if let Some(dirs) = APP_DIRS.get() {
+        return dirs;
+    }
+    let args: Vec<String> = std::env::args().collect();
+    init(&args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("abuseapp-paths-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn config_dir_flag_forms() {
+        let dir = std::env::temp_dir().join("portable");
+        let dir_arg = dir.to_string_lossy().to_string();
+        assert_eq!(config_dir_from_args(&args(&["app", "--config-dir", &dir_arg])), Some(dir.clone()));
+        assert_eq!(
+            config_dir_from_args(&args(&["app", &format!("--config-dir={}", dir_arg)])),
+            Some(dir)
+        );
+        assert_eq!(config_dir_from_args(&args(&["app", "--config-dir", "rel"])), Some(cwd().join("rel")));
+        assert_eq!(config_dir_from_args(&args(&["app", "--config-dir=  "])), None);
+        assert_eq!(config_dir_from_args(&args(&["app", "--config-dir"])), None);
+        assert_eq!(config_dir_from_args(&args(&["app", "--config-directory=x"])), None);
+    }
+
+    #[test]
+    fn config_dir_flag_puts_everything_in_one_folder() {
+        let dir = std::env::temp_dir().join("portable");
+        let dirs = resolve(&args(&["app", "--config-dir", &dir.to_string_lossy()]));
+        assert!(dirs.overridden);
+        assert_eq!(dirs.config, dir);
+        assert_eq!(dirs.data, dir);
+    }
+
+    #[test]
+    fn legacy_files_move_next_to_the_settings() {
+        let legacy = temp_dir();
+        let target = temp_dir().join("app");
+        fs::write(legacy.join(LEGACY_CWD_SETTINGS), "{}").unwrap();
+        fs::write(legacy.join("accounts.json"), "[]").unwrap();
+        fs::create_dir_all(legacy.join("journal")).unwrap();
+        fs::write(legacy.join("journal").join("2026-10-18.jsonl"), "{}").unwrap();
+        fs::write(legacy.join("links.json"), "old").unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("links.json"), "new").unwrap();
+
+        let dirs = AppDirs {
+            config: target.clone(),
+            data: target.clone(),
+            overridden: false,
+        };
+        migrate_legacy_cwd_files(&legacy, &dirs);
+
+        assert!(target.join("settings.json").is_file());
+        assert!(!legacy.join(LEGACY_CWD_SETTINGS).exists());
+        assert!(target.join("accounts.json").is_file());
+        assert!(target.join("journal").join("2026-10-18.jsonl").is_file());
+        assert!(!legacy.join("journal").exists());
+        // Existing destination files win; the legacy copy stays where it was.
+        assert_eq!(fs::read_to_string(target.join("links.json")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(legacy.join("links.json")).unwrap(), "old");
+        let _ = fs::remove_dir_all(legacy);
+        let _ = fs::remove_dir_all(target.parent().unwrap());
+    }
+
+    #[test]
+    fn data_files_stay_without_the_legacy_settings() {
+        let legacy = temp_dir();
+        let target = temp_dir();
+        fs::write(legacy.join("accounts.json"), "[]").unwrap();
+
+        let dirs = AppDirs {
+            config: target.clone(),
+            data: target.clone(),
+            overridden: false,
+        };
+        migrate_legacy_cwd_files(&legacy, &dirs);
+
+        assert!(legacy.join("accounts.json").is_file());
+        assert!(!target.join("accounts.json").exists());
+        let _ = fs::remove_dir_all(legacy);
+        let _ = fs::remove_dir_all(target);
+    }
+}
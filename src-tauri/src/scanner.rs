@@ -0,0 +1,618 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+
+use crate::accounts::{account_key, AccountKind};
+use crate::layout::{self, AccountFolder, AccountLayout};
+use crate::tdata::{self, TdataHealth, TdataReport};
+
+/// A watched root is still listed in full this often, in case the watcher missed something.
+const FULL_RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+/// Roots nobody asked about for this long are dropped, except the active workspace's.
+const IDLE_ROOT_TTL: Duration = Duration::from_secs(600);
+
+static SCANS: OnceLock<Mutex<HashMap<String, Arc<TrackedRoot>>>> = OnceLock::new();
+
+/// One account folder in `get_account_stats_detailed`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStatusRow {
+    /// Account registry key (`telegram:<folder>`), stable across scans and renumbering.
+    pub key: String,
+    pub folder: String,
+    pub number: Option<i32>,
+    /// Which aggregate the account is counted in: `running`, `blocked` or `unknown`.
+    pub status: &'static str,
+    pub pids: Vec<u32>,
+    pub health: TdataHealth,
+    /// Why the account is counted as blocked.
+    pub reason: Option<String>,
+    pub evidence: Vec<String>,
+    pub tdata_size_bytes: u64,
+    pub tdata_modified_at: Option<DateTime<Utc>>,
+    pub folder_modified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStats {
+    pub total: i64,
+    pub running: i64,
+    pub active: i64,
+    pub blocked: i64,
+    pub unknown: i64,
+    pub telegram_folder_path: String,
+    pub scanned_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum AccountChange {
+    Added { account: AccountStatusRow },
+    Removed { key: String, folder: String },
+    /// The status bucket or the tdata health moved.
+    StatusChanged {
+        previous_status: &'static str,
+        previous_health: TdataHealth,
+        account: AccountStatusRow,
+    },
+}
+
+/// What one background pass changed under a Telegram root.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountScanDiff {
+    pub telegram_folder_path: String,
+    pub changes: Vec<AccountChange>,
+    pub stats: AccountStats,
+}
+
+/// Folders the watcher saw change since the last pass.
+#[derive(Debug, Default)]
+struct Pending {
+    folders: HashSet<String>,
+    /// The watcher lost events or failed; only a full listing is trustworthy.
+    full: bool,
+}
+
+/// An account folder together with the expensive parts of its last inspection.
+struct CachedAccount {
+    folder: AccountFolder,
+    folder_modified_at: Option<DateTime<Utc>>,
+    tdata_modified_at: Option<DateTime<Utc>>,
+    report: TdataReport,
+    tdata_size_bytes: u64,
+    pids: Vec<u32>,
+}
+
+impl CachedAccount {
+    fn inspect(folder: AccountFolder) -> Self {
+        let tdata_path = folder.path.join("tdata");
+        Self {
+            folder_modified_at: layout::modified(&folder.path),
+            tdata_modified_at: layout::modified(&tdata_path),
+            report: tdata::inspect(&tdata_path),
+            tdata_size_bytes: layout::dir_size(&tdata_path),
+            pids: Vec::new(),
+            folder,
+        }
+    }
+
+    /// Whether the folder and its `tdata` still carry the modification times seen last time.
+    fn is_current(&self, folder: &AccountFolder) -> bool {
+        self.folder.has_exe == folder.has_exe
+            && self.folder.has_tdata == folder.has_tdata
+            && self.folder_modified_at == layout::modified(&folder.path)
+            && self.tdata_modified_at == layout::modified(&folder.path.join("tdata"))
+    }
+
+    fn status(&self) -> &'static str {
+        if !self.pids.is_empty() {
+            "running"
+        } else if !self.report.health.is_authorized() {
+            "blocked"
+        } else {
+            "unknown"
+        }
+    }
+
+    fn row(&self) -> AccountStatusRow {
+        let status = self.status();
+        AccountStatusRow {
+            key: account_key(AccountKind::Telegram, &self.folder.name),
+            folder: self.folder.name.clone(),
+            number: self.folder.number,
+            status,
+            pids: self.pids.clone(),
+            health: self.report.health,
+            reason: if status == "blocked" { self.report.reason.clone() } else { None },
+            evidence: self.report.evidence.clone(),
+            tdata_size_bytes: self.tdata_size_bytes,
+            tdata_modified_at: self.tdata_modified_at,
+            folder_modified_at: self.folder_modified_at,
+        }
+    }
+}
+
+/// Scan state of one Telegram root; only the background pass and the first scan touch it.
+struct RootScan {
+    root: PathBuf,
+    layout: AccountLayout,
+    accounts: HashMap<String, CachedAccount>,
+    /// Folder names in [`AccountLayout::scan`] order.
+    order: Vec<String>,
+    root_modified_at: Option<DateTime<Utc>>,
+    last_full_scan: Instant,
+    pending: Arc<Mutex<Pending>>,
+    /// Kept alive for as long as the root is tracked; `None` falls back to full listings.
+    watcher: Option<RecommendedWatcher>,
+}
+
+/// What readers get without waiting for a pass to finish.
+#[derive(Clone)]
+struct Snapshot {
+    stats: AccountStats,
+    rows: Arc<Vec<AccountStatusRow>>,
+}
+
+/// A root in the registry. The layout is fixed for the entry's lifetime (a new layout gets a
+/// new entry), so lookups never wait on a running pass.
+struct TrackedRoot {
+    root: PathBuf,
+    layout: AccountLayout,
+    /// Built by the first caller; concurrent callers wait for that scan instead of starting
+    /// their own.
+    state: OnceLock<ScanState>,
+    last_used: Mutex<Instant>,
+}
+
+struct ScanState {
+    scan: Mutex<RootScan>,
+    snapshot: Mutex<Snapshot>,
+}
+
+impl TrackedRoot {
+    fn state(&self) -> &ScanState {
+        self.state.get_or_init(|| {
+            let scan = RootScan::new(&self.root, &self.layout);
+            ScanState {
+                snapshot: Mutex::new(scan.snapshot()),
+                scan: Mutex::new(scan),
+            }
+        })
+    }
+
+    /// Runs `pass` on the scan and publishes the new snapshot; `None` when the lock is poisoned.
+    fn update(&self, pass: impl FnOnce(&mut RootScan) -> Vec<AccountChange>) -> Option<AccountScanDiff> {
+        let state = self.state();
+        let mut scan = state.scan.lock().ok()?;
+        let changes = pass(&mut scan);
+        let snapshot = scan.snapshot();
+        drop(scan);
+        let diff = AccountScanDiff {
+            telegram_folder_path: snapshot.stats.telegram_folder_path.clone(),
+            changes,
+            stats: snapshot.stats.clone(),
+        };
+        if let Ok(mut current) = state.snapshot.lock() {
+            *current = snapshot;
+        }
+        Some(diff)
+    }
+}
+
+fn scans() -> &'static Mutex<HashMap<String, Arc<TrackedRoot>>> {
+    SCANS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// First path component below `root`, i.e. the account folder an event belongs to.
+fn folder_of(root: &Path, path: &Path) -> Option<String> {
+    let first = path.strip_prefix(root).ok()?.components().next()?;
+    Some(first.as_os_str().to_string_lossy().to_string())
+}
+
+fn watch_root(root: &Path, pending: Arc<Mutex<Pending>>) -> Result<RecommendedWatcher, String> {
+    let watched_root = root.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(mut pending) = pending.lock() else {
+            return;
+        };
+        match result {
+            // Reads, including our own inspections, say nothing about the account.
+            Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+            Ok(event) if event.need_rescan() => pending.full = true,
+            Ok(event) => {
+                let folders = event.paths.iter().filter_map(|path| folder_of(&watched_root, path));
+                pending.folders.extend(folders);
+            }
+            Err(_) => pending.full = true,
+        }
+    })
+    .map_err(|e| format!("Failed to create folder watcher: {}", e))?;
+    watcher
+        .watch(root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+    Ok(watcher)
+}
+
+/// Running Telegram PIDs per account folder, by exe path or by a `/<folder>/` path segment.
+/// A process is claimed by the first account it matches, so no PID is counted twice.
+fn match_account_pids<'a>(
+    processes: &[(u32, String)],
+    accounts: impl Iterator<Item = &'a AccountFolder>,
+) -> Vec<Vec<u32>> {
+    let mut claimed: HashSet<u32> = HashSet::new();
+    accounts
+        .map(|account| {
+            let account_path_lower = account
+                .path
+                .to_string_lossy()
+                .to_lowercase()
+                .replace('\\', "/");
+            let account_name_segment = format!("/{}/", account.name.to_lowercase());
+            let pids: Vec<u32> = processes
+                .iter()
+                .filter(|(pid, process_path)| {
+                    let path_matches = process_path == &account_path_lower
+                        || process_path.starts_with(&(account_path_lower.clone() + "/"));
+                    let name_matches = process_path.contains(&account_name_segment);
+                    !claimed.contains(pid) && (path_matches || name_matches)
+                })
+                .map(|(pid, _)| *pid)
+                .collect();
+            claimed.extend(&pids);
+            pids
+        })
+        .collect()
+}
+
+fn running_processes() -> Vec<(u32, String)> {
+    crate::list_running_telegram_processes()
+        .into_iter()
+        .map(|(pid, _, path)| (pid, path.to_lowercase().replace('\\', "/")))
+        .collect()
+}
+
+impl RootScan {
+    fn new(root: &Path, layout: &AccountLayout) -> Self {
+        let pending = Arc::new(Mutex::new(Pending::default()));
+        let watcher = match watch_root(root, pending.clone()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                println!("[LOG] {}; account scans fall back to full listings", e);
+                None
+            }
+        };
+        let mut scan = Self {
+            root: root.to_path_buf(),
+            layout: layout.clone(),
+            accounts: HashMap::new(),
+            order: Vec::new(),
+            root_modified_at: None,
+            last_full_scan: Instant::now(),
+            pending,
+            watcher,
+        };
+        scan.full_listing(&HashSet::new());
+        scan.match_processes(&running_processes());
+        scan
+    }
+
+    /// Lists the root and re-inspects folders whose modification times moved or that the
+    /// watcher flagged. Everything else keeps its cached inspection.
+    fn full_listing(&mut self, flagged: &HashSet<String>) {
+        let mut previous = std::mem::take(&mut self.accounts);
+        for folder in self.layout.scan(&self.root) {
+            let cached = previous
+                .remove(&folder.name)
+                .filter(|cached| !flagged.contains(&folder.name) && cached.is_current(&folder));
+            let account = match cached {
+                Some(mut cached) => {
+                    cached.folder = folder;
+                    cached
+                }
+                None => CachedAccount::inspect(folder),
+            };
+            self.accounts.insert(account.folder.name.clone(), account);
+        }
+        self.root_modified_at = layout::modified(&self.root);
+        self.last_full_scan = Instant::now();
+    }
+
+    /// Re-inspects only the folders the watcher flagged.
+    fn rescan_folders(&mut self, flagged: &HashSet<String>) {
+        for name in flagged {
+            match self.layout.account_folder(&self.root.join(name)) {
+                Some(folder) => {
+                    self.accounts.insert(name.clone(), CachedAccount::inspect(folder));
+                }
+                None => {
+                    self.accounts.remove(name);
+                }
+            }
+        }
+    }
+
+    fn match_processes(&mut self, processes: &[(u32, String)]) {
+        let mut folders: Vec<&AccountFolder> = self.accounts.values().map(|account| &account.folder).collect();
+        folders.sort_by_cached_key(|folder| folder.sort_key());
+        self.order = folders.iter().map(|folder| folder.name.clone()).collect();
+        let account_pids = match_account_pids(processes, folders.into_iter());
+        for (name, pids) in self.order.iter().zip(account_pids) {
+            if let Some(account) = self.accounts.get_mut(name) {
+                account.pids = pids;
+            }
+        }
+    }
+
+    fn statuses(&self) -> HashMap<String, (&'static str, TdataHealth)> {
+        self.accounts
+            .iter()
+            .map(|(name, account)| (name.clone(), (account.status(), account.report.health)))
+            .collect()
+    }
+
+    /// One incremental pass; returns what changed since the previous one.
+    fn refresh(&mut self, processes: &[(u32, String)]) -> Vec<AccountChange> {
+        let pending = self
+            .pending
+            .lock()
+            .map(|mut pending| std::mem::take(&mut *pending))
+            .unwrap_or_default();
+        let before = self.statuses();
+
+        let full = pending.full
+            || self.watcher.is_none()
+            || self.last_full_scan.elapsed() >= FULL_RESCAN_INTERVAL
+            || layout::modified(&self.root) != self.root_modified_at;
+        if full {
+            self.full_listing(&pending.folders);
+        } else {
+            self.rescan_folders(&pending.folders);
+        }
+        self.match_processes(processes);
+        self.changes_since(&before)
+    }
+
+    /// Matches a fresh process list without touching the folders.
+    fn rematch(&mut self, processes: &[(u32, String)]) -> Vec<AccountChange> {
+        let before = self.statuses();
+        self.match_processes(processes);
+        self.changes_since(&before)
+    }
+
+    fn changes_since(&self, before: &HashMap<String, (&'static str, TdataHealth)>) -> Vec<AccountChange> {
+        let mut changes = Vec::new();
+        for name in &self.order {
+            let account = &self.accounts[name];
+            match before.get(name) {
+                None => changes.push(AccountChange::Added { account: account.row() }),
+                Some(&(status, health)) if status != account.status() || health != account.report.health => {
+                    changes.push(AccountChange::StatusChanged {
+                        previous_status: status,
+                        previous_health: health,
+                        account: account.row(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        let mut removed: Vec<&String> = before.keys().filter(|name| !self.accounts.contains_key(*name)).collect();
+        removed.sort();
+        changes.extend(removed.into_iter().map(|name| AccountChange::Removed {
+            key: account_key(AccountKind::Telegram, name),
+            folder: name.clone(),
+        }));
+        changes
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let rows: Vec<AccountStatusRow> = self.order.iter().map(|name| self.accounts[name].row()).collect();
+        let count = |status: &str| rows.iter().filter(|row| row.status == status).count() as i64;
+        let running = count("running");
+        Snapshot {
+            stats: AccountStats {
+                total: rows.len() as i64,
+                running,
+                active: running,
+                blocked: count("blocked"),
+                unknown: count("unknown"),
+                telegram_folder_path: self.root.to_string_lossy().to_string(),
+                scanned_at: Utc::now(),
+            },
+            rows: Arc::new(rows),
+        }
+    }
+}
+
+/// The tracked entry for `root`; a new root or a changed layout gets a fresh entry, scanned
+/// by whoever reads it first.
+fn tracked(root: &Path, layout: &AccountLayout) -> Result<Arc<TrackedRoot>, String> {
+    let key = crate::normalize_path_for_match(&root.to_string_lossy());
+    let mut scans = scans()
+        .lock()
+        .map_err(|_| "Account scan registry lock is poisoned".to_string())?;
+    if let Some(entry) = scans.get(&key).filter(|entry| entry.layout == *layout) {
+        if let Ok(mut last_used) = entry.last_used.lock() {
+            *last_used = Instant::now();
+        }
+        return Ok(entry.clone());
+    }
+    let entry = Arc::new(TrackedRoot {
+        root: root.to_path_buf(),
+        layout: layout.clone(),
+        state: OnceLock::new(),
+        last_used: Mutex::new(Instant::now()),
+    });
+    scans.insert(key, entry.clone());
+    Ok(entry)
+}
+
+/// Blocks for the first scan of a new root; run it off the async runtime.
+fn snapshot_of(root: &Path, layout: &AccountLayout) -> Result<Snapshot, String> {
+    tracked(root, layout)?
+        .state()
+        .snapshot
+        .lock()
+        .map(|snapshot| snapshot.clone())
+        .map_err(|e| format!("Failed to read account scan: {}", e))
+}
+
+/// Aggregate counts for `root` from the last pass; only the first call for a root scans it.
+pub fn stats(root: &Path, layout: &AccountLayout) -> Result<AccountStats, String> {
+    snapshot_of(root, layout).map(|snapshot| snapshot.stats)
+}
+
+/// [`stats`] plus one row per account folder.
+pub fn rows(root: &Path, layout: &AccountLayout) -> Result<(AccountStats, Vec<AccountStatusRow>), String> {
+    snapshot_of(root, layout).map(|snapshot| (snapshot.stats, snapshot.rows.as_ref().clone()))
+}
+
+/// One background pass over every tracked root. `active` (the active workspace's root) is
+/// always tracked; other roots stay only while commands keep asking about them.
+/// A root's first scan reports no changes; readers get it from [`stats`] or [`rows`].
+pub fn refresh(active: Option<(&Path, &AccountLayout)>) -> Vec<AccountScanDiff> {
+    let active_key = active.and_then(|(root, layout)| {
+        tracked(root, layout).ok()?;
+        Some(crate::normalize_path_for_match(&root.to_string_lossy()))
+    });
+    let entries: Vec<Arc<TrackedRoot>> = match scans().lock() {
+        Ok(mut scans) => {
+            scans.retain(|key, entry| {
+                Some(key) == active_key.as_ref()
+                    || entry
+                        .last_used
+                        .lock()
+                        .map(|last_used| last_used.elapsed() < IDLE_ROOT_TTL)
+                        .unwrap_or(false)
+            });
+            scans.values().cloned().collect()
+        }
+        Err(_) => return Vec::new(),
+    };
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    let processes = running_processes();
+    entries
+        .iter()
+        .filter_map(|entry| entry.update(|scan| scan.refresh(&processes)))
+        .filter(|diff| !diff.changes.is_empty())
+        .collect()
+}
+
+/// Re-matches running processes for every root that has been scanned, e.g. right after a
+/// launch or close, so `running` does not wait for the next pass.
+pub fn refresh_processes() -> Vec<AccountScanDiff> {
+    let entries: Vec<Arc<TrackedRoot>> = match scans().lock() {
+        Ok(scans) => scans
+            .values()
+            .filter(|entry| entry.state.get().is_some())
+            .cloned()
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    let processes = running_processes();
+    entries
+        .iter()
+        .filter_map(|entry| entry.update(|scan| scan.rematch(&processes)))
+        .filter(|diff| !diff.changes.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("abuseapp-scan-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn add_account(root: &Path, folder: &str) {
+        fs::create_dir_all(root.join(folder).join("tdata")).unwrap();
+    }
+
+    fn process_in(root: &Path, folder: &str, pid: u32) -> (u32, String) {
+        let exe = root.join(folder).join("Telegram.exe");
+        (pid, exe.to_string_lossy().to_lowercase().replace('\\', "/"))
+    }
+
+    /// Root modification times can be coarse; make sure the next change is seen as one.
+    fn settle() {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    #[test]
+    fn refresh_reports_added_status_changed_and_removed() {
+        let root = temp_root();
+        add_account(&root, "TG 1");
+        let mut scan = RootScan::new(&root, &AccountLayout::default());
+        assert!(scan.refresh(&[]).is_empty());
+
+        settle();
+        add_account(&root, "TG 2");
+        let changes = scan.refresh(&[]);
+        assert_eq!(changes.len(), 1);
+        assert!(
+            matches!(&changes[0], AccountChange::Added { account } if account.folder == "TG 2"),
+            "{:?}",
+            changes
+        );
+
+        let changes = scan.refresh(&[process_in(&root, "TG 1", 42)]);
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            AccountChange::StatusChanged { previous_status, account, .. } => {
+                assert_eq!(*previous_status, "blocked");
+                assert_eq!(account.status, "running");
+                assert_eq!(account.pids, vec![42]);
+            }
+            other => panic!("unexpected change {:?}", other),
+        }
+
+        settle();
+        fs::remove_dir_all(root.join("TG 2")).unwrap();
+        let changes = scan.refresh(&[process_in(&root, "TG 1", 42)]);
+        assert_eq!(changes.len(), 1);
+        assert!(
+            matches!(&changes[0], AccountChange::Removed { key, folder } if folder == "TG 2" && key == "telegram:TG 2"),
+            "{:?}",
+            changes
+        );
+        assert_eq!(scan.snapshot().stats.total, 1);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn rematch_only_reports_process_changes() {
+        let root = temp_root();
+        add_account(&root, "TG 1");
+        add_account(&root, "TG 2");
+        let mut scan = RootScan::new(&root, &AccountLayout::default());
+        scan.refresh(&[]);
+
+        let changes = scan.rematch(&[process_in(&root, "TG 2", 7)]);
+        assert_eq!(changes.len(), 1);
+        assert!(
+            matches!(&changes[0], AccountChange::StatusChanged { account, .. } if account.folder == "TG 2"),
+            "{:?}",
+            changes
+        );
+        assert!(scan.rematch(&[process_in(&root, "TG 2", 7)]).is_empty());
+        assert_eq!(scan.snapshot().stats.running, 1);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::launcher::{DeepLinkStatus, ProfileReport, ProfileStatus};
+use crate::store;
+use crate::TelegramLink;
+
+static SESSIONS_LOCK: Mutex<()> = Mutex::new(());
+
+const MAX_STORED_SESSIONS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Running,
+    Completed,
+    Cancelled,
+    /// The app exited while the session was still running.
+    Interrupted,
+}
+
+/// Checkpoint of one Telegram batch launch, rewritten after every profile outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchSession {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub status: SessionStatus,
+    pub link_params: TelegramLink,
+    pub telegram_folder_path: String,
+    /// Full launch order of the original request.
+    pub profiles: Vec<i32>,
+    /// Folders for stand-in profile ids of accounts outside the folder layout.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub folders: HashMap<i32, String>,
+    #[serde(default)]
+    pub spawned: Vec<i32>,
+    #[serde(default)]
+    pub deep_linked: Vec<i32>,
+    #[serde(default)]
+    pub failed: Vec<i32>,
+    /// Link steps already sent to profiles whose multi-step sequence stopped part way.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub steps_sent: HashMap<i32, usize>,
+}
+
+fn push_unique(list: &mut Vec<i32>, profile: i32) {
+    if !list.contains(&profile) {
+        list.push(profile);
+    }
+}
+
+impl LaunchSession {
+    pub fn new(
+        link_params: TelegramLink,
+        telegram_folder_path: String,
+        profiles: Vec<i32>,
+        folders: HashMap<i32, String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: now,
+            updated_at: now,
+            status: SessionStatus::Running,
+            link_params,
+            telegram_folder_path,
+            profiles,
+            folders,
+            spawned: Vec::new(),
+            deep_linked: Vec::new(),
+            failed: Vec::new(),
+            steps_sent: HashMap::new(),
+        }
+    }
+
+    /// Folds one outcome into the checkpoint. A resumed run only gets the steps a profile
+    /// still owes, so `outcome.steps_sent` adds to what earlier runs sent.
+    pub fn record_outcome(&mut self, outcome: &ProfileReport) {
+        if outcome.pid.is_some() {
+            push_unique(&mut self.spawned, outcome.profile);
+        }
+        match outcome.deep_link {
+            DeepLinkStatus::Sent => {
+                push_unique(&mut self.deep_linked, outcome.profile);
+                self.failed.retain(|profile| *profile != outcome.profile);
+                self.steps_sent.remove(&outcome.profile);
+            }
+            DeepLinkStatus::Partial if outcome.steps_sent > 0 => {
+                *self.steps_sent.entry(outcome.profile).or_default() += outcome.steps_sent;
+            }
+            _ => {}
+        }
+        if outcome.status == ProfileStatus::Failed {
+            push_unique(&mut self.failed, outcome.profile);
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Profiles still owed a deep link, in the original order. Failed profiles are only
+    /// included when `retry_failed` is set, since most failures (missing exe) repeat.
+    pub fn remaining_profiles(&self, retry_failed: bool) -> Vec<i32> {
+        self.profiles
+            .iter()
+            .copied()
+            .filter(|profile| !self.deep_linked.contains(profile))
+            .filter(|profile| retry_failed || !self.failed.contains(profile))
+            .collect()
+    }
+}
+
+fn session_file(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+fn validate_id(id: &str) -> Result<(), String> {
+    uuid::Uuid::parse_str(id)
+        .map(|_| ())
+        .map_err(|_| format!("Invalid launch session id: {}", id))
+}
+
+fn load_session(dir: &Path, id: &str) -> Result<LaunchSession, String> {
+    validate_id(id)?;
+    let path = session_file(dir, id);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read launch session {}: {}", id, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse launch session {}: {}", id, e))
+}
+
+fn list_unlocked(dir: &Path) -> Vec<LaunchSession> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<LaunchSession> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str::<LaunchSession>(&content).ok())
+        .collect();
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.created_at));
+    sessions
+}
+
+/// All stored sessions, newest first.
+pub fn list(dir: &Path) -> Result<Vec<LaunchSession>, String> {
+    let _guard = SESSIONS_LOCK
+        .lock()
+        .map_err(|_| "Launch session lock is poisoned".to_string())?;
+    Ok(list_unlocked(dir))
+}
+
+/// Stores a new session and drops the oldest checkpoints beyond the retention limit.
+pub fn create(dir: &Path, session: &LaunchSession) -> Result<(), String> {
+    let _guard = SESSIONS_LOCK
+        .lock()
+        .map_err(|_| "Launch session lock is poisoned".to_string())?;
+    store::save_json(&session_file(dir, &session.id), session, "launch session")?;
+    for stale in list_unlocked(dir).iter().skip(MAX_STORED_SESSIONS) {
+        let _ = fs::remove_file(session_file(dir, &stale.id));
+    }
+    Ok(())
+}
+
+/// Runs a load/mutate/save cycle on one session checkpoint under a process-wide lock.
+pub fn with_session<T>(
+    dir: &Path,
+    id: &str,
+    mutate: impl FnOnce(&mut LaunchSession) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = SESSIONS_LOCK
+        .lock()
+        .map_err(|_| "Launch session lock is poisoned".to_string())?;
+    let mut session = load_session(dir, id)?;
+    let result = mutate(&mut session)?;
+    store::save_json(&session_file(dir, id), &session, "launch session")?;
+    Ok(result)
+}
+
+pub fn delete(dir: &Path, id: &str) -> Result<(), String> {
+    let _guard = SESSIONS_LOCK
+        .lock()
+        .map_err(|_| "Launch session lock is poisoned".to_string())?;
+    validate_id(id)?;
+    fs::remove_file(session_file(dir, id)).map_err(|e| format!("Failed to delete launch session {}: {}", id, e))
+}
+
+/// Sessions left `Running` by a previous process can never finish; flag them as interrupted.
+pub fn mark_interrupted(dir: &Path) -> Result<usize, String> {
+    let _guard = SESSIONS_LOCK
+        .lock()
+        .map_err(|_| "Launch session lock is poisoned".to_string())?;
+    let mut marked = 0;
+    for mut session in list_unlocked(dir) {
+        if session.status != SessionStatus::Running {
+            continue;
+        }
+        session.status = SessionStatus::Interrupted;
+        store::save_json(&session_file(dir, &session.id), &session, "launch session")?;
+        marked += 1;
+    }
+    Ok(marked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(profiles: Vec<i32>) -> LaunchSession {
+        LaunchSession::new(TelegramLink::default(), "C:\\Telegram".to_string(), profiles, HashMap::new())
+    }
+
+    fn outcome(profile: i32, status: ProfileStatus, deep_link: DeepLinkStatus, steps_sent: usize) -> ProfileReport {
+        let mut report = ProfileReport::new(format!("TG {}", profile), profile, Path::new("Telegram.exe"));
+        report.status = status;
+        report.pid = (status == ProfileStatus::Launched).then_some(1000 + profile as u32);
+        report.deep_link = deep_link;
+        report.steps_sent = steps_sent;
+        report
+    }
+
+    #[test]
+    fn partial_deep_links_accumulate_steps_until_sent() {
+        let mut session = session(vec![1, 2]);
+        session.record_outcome(&outcome(1, ProfileStatus::Launched, DeepLinkStatus::Partial, 1));
+        session.record_outcome(&outcome(1, ProfileStatus::Launched, DeepLinkStatus::Partial, 2));
+        session.record_outcome(&outcome(2, ProfileStatus::Launched, DeepLinkStatus::Sent, 3));
+
+        assert_eq!(session.steps_sent.get(&1), Some(&3));
+        assert_eq!(session.spawned, vec![1, 2]);
+        assert_eq!(session.deep_linked, vec![2]);
+        assert_eq!(session.remaining_profiles(false), vec![1]);
+
+        session.record_outcome(&outcome(1, ProfileStatus::Launched, DeepLinkStatus::Sent, 1));
+        assert!(session.steps_sent.is_empty());
+        assert!(session.remaining_profiles(true).is_empty());
+    }
+
+    #[test]
+    fn failed_profiles_are_only_retried_on_request() {
+        let mut session = session(vec![1, 2, 3]);
+        session.record_outcome(&outcome(1, ProfileStatus::Failed, DeepLinkStatus::NotSent, 0));
+        session.record_outcome(&outcome(2, ProfileStatus::Launched, DeepLinkStatus::Sent, 1));
+
+        assert_eq!(session.failed, vec![1]);
+        assert_eq!(session.remaining_profiles(false), vec![3]);
+        assert_eq!(session.remaining_profiles(true), vec![1, 3]);
+
+        session.record_outcome(&outcome(1, ProfileStatus::Launched, DeepLinkStatus::Sent, 1));
+        assert!(session.failed.is_empty());
+        assert_eq!(session.remaining_profiles(false), vec![3]);
+    }
+
+    #[test]
+    fn cancelled_profiles_stay_remaining() {
+        let mut session = session(vec![1, 2]);
+        session.record_outcome(&outcome(1, ProfileStatus::Cancelled, DeepLinkStatus::NotSent, 0));
+        session.record_outcome(&outcome(2, ProfileStatus::Cancelled, DeepLinkStatus::Partial, 0));
+
+        assert!(session.spawned.is_empty());
+        assert!(session.failed.is_empty());
+        assert!(session.steps_sent.is_empty());
+        assert_eq!(session.remaining_profiles(false), vec![1, 2]);
+    }
+}
@@ -0,0 +1,978 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::layout::AccountLayout;
+use crate::store;
+
+/// Bump together with a new entry in `MIGRATIONS`.
+pub const SETTINGS_VERSION: u32 = 3;
+
+/// Id of the workspace that existing single-farm settings migrate into.
+pub const DEFAULT_WORKSPACE_ID: &str = "default";
+
+const MAX_THREADS: u32 = 64;
+const DEFAULT_WATCHDOG_RESTARTS: u32 = 3;
+const MAX_WATCHDOG_RESTARTS: u32 = 20;
+/// Upper bound for any single timing value, so a typo cannot stall a launch for hours.
+const MAX_TIMING_MS: u64 = 600_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchSpeed {
+    Fast,
+    Balanced,
+    #[default]
+    Conservative,
+    /// Use the workspace's own timing profile instead of a preset.
+    Custom,
+}
+
+impl LaunchSpeed {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "fast" => Some(LaunchSpeed::Fast),
+            "balanced" => Some(LaunchSpeed::Balanced),
+            "conservative" => Some(LaunchSpeed::Conservative),
+            "custom" => Some(LaunchSpeed::Custom),
+            _ => None,
+        }
+    }
+
+    /// Built-in Telegram pacing; `None` for `Custom`.
+    pub fn telegram_preset(self) -> Option<TimingProfile> {
+        let (spawn_delay_ms, link_delay_ms) = match self {
+            LaunchSpeed::Fast => (1200, 700),
+            LaunchSpeed::Balanced => (2000, 1200),
+            LaunchSpeed::Conservative => (3000, 2000),
+            LaunchSpeed::Custom => return None,
+        };
+        Some(TimingProfile {
+            spawn_delay_ms,
+            link_delay_ms,
+            ..TimingProfile::default()
+        })
+    }
+
+    /// Built-in Chrome pacing; the URL goes out with the spawn, so there is no link delay.
+    pub fn chrome_preset(self) -> Option<TimingProfile> {
+        let spawn_delay_ms = match self {
+            LaunchSpeed::Fast => 300,
+            LaunchSpeed::Balanced => 600,
+            LaunchSpeed::Conservative => 1200,
+            LaunchSpeed::Custom => return None,
+        };
+        Some(TimingProfile {
+            spawn_delay_ms,
+            ..TimingProfile::default()
+        })
+    }
+}
+
+/// Pacing for one launcher. Every spawn and link wait gets a random extra delay drawn
+/// from `jitter_min_ms..=jitter_max_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimingProfile {
+    /// Wait after starting a client before sending its link (Telegram) or starting the next one.
+    pub spawn_delay_ms: u64,
+    /// Wait after each link; for Chrome, a non-zero value opens the URL separately after this delay.
+    pub link_delay_ms: u64,
+    pub jitter_min_ms: u64,
+    pub jitter_max_ms: u64,
+    /// Pause after every batch of `threads` launches.
+    pub batch_cooldown_ms: u64,
+}
+
+impl TimingProfile {
+    fn validate(&self, label: &str, errors: &mut Vec<String>) {
+        let values = [
+            ("spawnDelayMs", self.spawn_delay_ms),
+            ("linkDelayMs", self.link_delay_ms),
+            ("jitterMinMs", self.jitter_min_ms),
+            ("jitterMaxMs", self.jitter_max_ms),
+            ("batchCooldownMs", self.batch_cooldown_ms),
+        ];
+        for (key, value) in values {
+            if value > MAX_TIMING_MS {
+                errors.push(format!("{}.{} must be at most {} ms", label, key, MAX_TIMING_MS));
+            }
+        }
+        if self.jitter_min_ms > self.jitter_max_ms {
+            errors.push(format!("{}: jitterMinMs must not exceed jitterMaxMs", label));
+        }
+    }
+
+    /// A fresh random extra delay; zero when no jitter is configured.
+    pub fn jitter_ms(&self) -> u64 {
+        if self.jitter_max_ms == 0 {
+            return 0;
+        }
+        rand::thread_rng().gen_range(self.jitter_min_ms.min(self.jitter_max_ms)..=self.jitter_max_ms)
+    }
+
+    pub fn jittered(&self, ms: u64) -> u64 {
+        ms + self.jitter_ms()
+    }
+
+    /// The cooldown to wait before launch number `launched` (0-based) when launches run in
+    /// batches of `batch_size`; `None` inside a batch or without a cooldown.
+    pub fn cooldown_before(&self, launched: usize, batch_size: usize) -> Option<u64> {
+        let new_batch = launched > 0 && launched % batch_size.max(1) == 0;
+        (new_batch && self.batch_cooldown_ms > 0).then_some(self.batch_cooldown_ms)
+    }
+}
+
+/// One farm: its folders, pacing and (through its id) its own link catalog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    pub telegram_folder_path: String,
+    /// Which folders under `telegram_folder_path` are accounts.
+    pub account_layout: AccountLayout,
+    pub telegram_threads: u32,
+    pub telegram_launch_speed: LaunchSpeed,
+    /// Used when `telegram_launch_speed` is `Custom`.
+    pub telegram_timing: TimingProfile,
+    pub chrome_folder_path: String,
+    pub chrome_threads: u32,
+    pub chrome_launch_speed: LaunchSpeed,
+    /// Used when `chrome_launch_speed` is `Custom`.
+    pub chrome_timing: TimingProfile,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self {
+            id: DEFAULT_WORKSPACE_ID.to_string(),
+            name: "Default".to_string(),
+            telegram_folder_path: String::new(),
+            account_layout: AccountLayout::default(),
+            telegram_threads: 1,
+            telegram_launch_speed: LaunchSpeed::default(),
+            telegram_timing: LaunchSpeed::Conservative.telegram_preset().unwrap_or_default(),
+            chrome_folder_path: String::new(),
+            chrome_threads: 1,
+            chrome_launch_speed: LaunchSpeed::Balanced,
+            chrome_timing: LaunchSpeed::Balanced.chrome_preset().unwrap_or_default(),
+        }
+    }
+}
+
+impl Workspace {
+    fn validate(&self, errors: &mut Vec<String>) {
+        let label = format!("Workspace \"{}\"", self.name);
+        if !(1..=MAX_THREADS).contains(&self.telegram_threads) {
+            errors.push(format!("{}: telegramThreads must be between 1 and {}", label, MAX_THREADS));
+        }
+        if !(1..=MAX_THREADS).contains(&self.chrome_threads) {
+            errors.push(format!("{}: chromeThreads must be between 1 and {}", label, MAX_THREADS));
+        }
+        if let Err(e) = self.account_layout.validate() {
+            errors.push(format!("{}: {}", label, e));
+        }
+        self.telegram_timing
+            .validate(&format!("{} telegramTiming", label), errors);
+        self.chrome_timing.validate(&format!("{} chromeTiming", label), errors);
+    }
+
+    /// The preset named by `telegram_launch_speed`, or the custom profile.
+    pub fn telegram_timing_profile(&self) -> TimingProfile {
+        self.telegram_launch_speed
+            .telegram_preset()
+            .unwrap_or(self.telegram_timing)
+    }
+
+    pub fn chrome_timing_profile(&self) -> TimingProfile {
+        self.chrome_launch_speed
+            .chrome_preset()
+            .unwrap_or(self.chrome_timing)
+    }
+}
+
+/// Workspace ids end up in file paths, so they are limited to a safe alphabet.
+pub fn validate_workspace_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid workspace id: {}", id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub version: u32,
+    pub active_workspace: String,
+    pub workspaces: Vec<Workspace>,
+    pub telegram_watchdog: bool,
+    pub telegram_watchdog_retries: u32,
+    /// Re-send the last deep link when the watchdog relaunches a crashed client.
+    pub telegram_watchdog_resend_link: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            active_workspace: DEFAULT_WORKSPACE_ID.to_string(),
+            workspaces: vec![Workspace::default()],
+            telegram_watchdog: false,
+            telegram_watchdog_retries: DEFAULT_WATCHDOG_RESTARTS,
+            telegram_watchdog_resend_link: true,
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        if self.workspaces.is_empty() {
+            errors.push("at least one workspace is required".to_string());
+        }
+        for (index, workspace) in self.workspaces.iter().enumerate() {
+            if let Err(e) = validate_workspace_id(&workspace.id) {
+                errors.push(e);
+            }
+            if workspace.name.trim().is_empty() {
+                errors.push(format!("Workspace {} needs a name", workspace.id));
+            }
+            let earlier = &self.workspaces[..index];
+            if earlier.iter().any(|other| other.id == workspace.id) {
+                errors.push(format!("Duplicate workspace id: {}", workspace.id));
+            }
+            if earlier
+                .iter()
+                .any(|other| other.name.trim().eq_ignore_ascii_case(workspace.name.trim()))
+            {
+                errors.push(format!("Duplicate workspace name: {}", workspace.name));
+            }
+            workspace.validate(&mut errors);
+        }
+        if !self.workspaces.iter().any(|workspace| workspace.id == self.active_workspace) {
+            errors.push(format!("Active workspace {} does not exist", self.active_workspace));
+        }
+        if self.telegram_watchdog_retries > MAX_WATCHDOG_RESTARTS {
+            errors.push(format!(
+                "telegramWatchdogRetries must be at most {}",
+                MAX_WATCHDOG_RESTARTS
+            ));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Invalid settings: {}", errors.join("; ")))
+        }
+    }
+
+    /// The active workspace; validation guarantees it exists.
+    pub fn active(&self) -> &Workspace {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.id == self.active_workspace)
+            .or_else(|| self.workspaces.first())
+            .expect("settings always hold at least one workspace")
+    }
+
+    fn workspace_mut(&mut self, id: &str) -> &mut Workspace {
+        let index = self
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.id == id)
+            .unwrap_or(0);
+        &mut self.workspaces[index]
+    }
+
+    /// Looks a workspace up by id or (case-insensitive) name; blank means the active one.
+    pub fn workspace(&self, key: Option<&str>) -> Result<&Workspace, String> {
+        let key = match key.map(str::trim).filter(|key| !key.is_empty()) {
+            Some(key) => key,
+            None => return Ok(self.active()),
+        };
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.id == key)
+            .or_else(|| {
+                self.workspaces
+                    .iter()
+                    .find(|workspace| workspace.name.trim().eq_ignore_ascii_case(key))
+            })
+            .ok_or_else(|| format!("Workspace not found: {}", key))
+    }
+
+    /// The workspace that owns a Telegram folder, so launches into it use that workspace's
+    /// pacing; unknown folders fall back to the active workspace.
+    pub fn workspace_for_telegram_folder(&self, folder: &str) -> &Workspace {
+        let folder = folder.trim().trim_end_matches(['/', '\\']);
+        self.workspaces
+            .iter()
+            .find(|workspace| {
+                let own = workspace.telegram_folder_path.trim_end_matches(['/', '\\']);
+                !own.is_empty() && own.eq_ignore_ascii_case(folder)
+            })
+            .unwrap_or_else(|| self.active())
+    }
+
+    /// Creates or replaces a workspace; a blank id creates a new one.
+    pub fn upsert_workspace(&mut self, mut workspace: Workspace) -> Result<Workspace, String> {
+        workspace.name = workspace.name.trim().to_string();
+        workspace.telegram_folder_path = workspace.telegram_folder_path.trim().to_string();
+        workspace.chrome_folder_path = workspace.chrome_folder_path.trim().to_string();
+        if workspace.id.trim().is_empty() {
+            workspace.id = uuid::Uuid::new_v4().to_string();
+        }
+        match self.workspaces.iter_mut().find(|existing| existing.id == workspace.id) {
+            Some(existing) => *existing = workspace.clone(),
+            None => self.workspaces.push(workspace.clone()),
+        }
+        self.validate()?;
+        Ok(workspace)
+    }
+
+    /// Removes a workspace; deleting the active one activates the first remaining workspace.
+    pub fn remove_workspace(&mut self, id: &str) -> Result<Workspace, String> {
+        let index = self
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.id == id)
+            .ok_or_else(|| format!("Workspace not found: {}", id))?;
+        if self.workspaces.len() == 1 {
+            return Err("Cannot delete the last workspace".to_string());
+        }
+        let removed = self.workspaces.remove(index);
+        if self.active_workspace == removed.id {
+            self.active_workspace = self.workspaces[0].id.clone();
+        }
+        Ok(removed)
+    }
+
+    /// Applies the keys present in `patch`, ignoring ones the backend does not own (theme,
+    /// language, ...). Folder, thread and speed keys edit the active workspace, or the one
+    /// named by `workspace` in the same patch; switching workspaces is left to
+    /// `set_active_workspace`. Numbers and flags may arrive as JSON values or as strings; a
+    /// blank string restores the default.
+    pub fn apply_patch(&mut self, patch: &Value) -> Result<(), String> {
+        let target = self
+            .workspace(patch.get("workspace").and_then(|v| v.as_str()))?
+            .id
+            .clone();
+
+        let defaults = Workspace::default();
+        let mut errors = Vec::new();
+        let mut number = |key: &str, target: &mut u32, default: u32| match patch.get(key) {
+            None | Some(Value::Null) => {}
+            Some(value) if is_blank(value) => *target = default,
+            Some(value) => match value_as_u32(value) {
+                Some(parsed) => *target = parsed,
+                None => errors.push(format!("{} must be a whole number, got {}", key, value)),
+            },
+        };
+        let workspace = self.workspace_mut(&target);
+        number("telegramThreads", &mut workspace.telegram_threads, defaults.telegram_threads);
+        number("chromeThreads", &mut workspace.chrome_threads, defaults.chrome_threads);
+        number(
+            "telegramWatchdogRetries",
+            &mut self.telegram_watchdog_retries,
+            DEFAULT_WATCHDOG_RESTARTS,
+        );
+
+        let mut flag = |key: &str, target: &mut bool, default: bool| match patch.get(key) {
+            None | Some(Value::Null) => {}
+            Some(value) if is_blank(value) => *target = default,
+            Some(value) => match value_as_bool(value) {
+                Some(parsed) => *target = parsed,
+                None => errors.push(format!("{} must be on/off, got {}", key, value)),
+            },
+        };
+        flag("telegramWatchdog", &mut self.telegram_watchdog, false);
+        flag("telegramWatchdogResendLink", &mut self.telegram_watchdog_resend_link, true);
+
+        let mut speed = |key: &str, target: &mut LaunchSpeed, default: LaunchSpeed| match patch.get(key) {
+            None | Some(Value::Null) => {}
+            Some(value) if is_blank(value) => *target = default,
+            Some(value) => match value.as_str().and_then(LaunchSpeed::parse) {
+                Some(parsed) => *target = parsed,
+                None => errors.push(format!(
+                    "{} must be fast, balanced, conservative or custom, got {}",
+                    key, value
+                )),
+            },
+        };
+        let workspace = self.workspace_mut(&target);
+        speed(
+            "telegramLaunchSpeed",
+            &mut workspace.telegram_launch_speed,
+            defaults.telegram_launch_speed,
+        );
+        speed(
+            "chromeLaunchSpeed",
+            &mut workspace.chrome_launch_speed,
+            defaults.chrome_launch_speed,
+        );
+
+        let mut timing = |key: &str, target: &mut TimingProfile| {
+            if let Some(value) = patch.get(key).filter(|value| !value.is_null()) {
+                match serde_json::from_value(value.clone()) {
+                    Ok(parsed) => *target = parsed,
+                    Err(e) => errors.push(format!("{} is not a valid timing profile: {}", key, e)),
+                }
+            }
+        };
+        timing("telegramTiming", &mut workspace.telegram_timing);
+        timing("chromeTiming", &mut workspace.chrome_timing);
+
+        if let Some(value) = patch.get("accountLayout").filter(|value| !value.is_null()) {
+            match serde_json::from_value(value.clone()) {
+                Ok(layout) => workspace.account_layout = layout,
+                Err(e) => errors.push(format!("accountLayout is not a valid folder layout: {}", e)),
+            }
+        }
+        if let Some(v) = patch.get("telegramFolderPath").and_then(|v| v.as_str()) {
+            workspace.telegram_folder_path = v.trim().to_string();
+        }
+        if let Some(v) = patch.get("chromeFolderPath").and_then(|v| v.as_str()) {
+            workspace.chrome_folder_path = v.trim().to_string();
+        }
+
+        if !errors.is_empty() {
+            return Err(format!("Invalid settings: {}", errors.join("; ")));
+        }
+        self.validate()
+    }
+}
+
+fn is_blank(value: &Value) -> bool {
+    value.as_str().map(|s| s.trim().is_empty()).unwrap_or(false)
+}
+
+fn value_as_u32(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "on" | "yes" | "true" | "1" => Some(true),
+            "off" | "no" | "false" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` document to version `n + 2`.
+const MIGRATIONS: [Migration; 2] = [migrate_v1_string_fields, migrate_v2_workspaces];
+
+/// Version 1 (no `version` key) stored every field as a string and fell back to defaults for
+/// anything unparsable; keep that behaviour once here instead of on every read.
+fn migrate_v1_string_fields(doc: &mut Map<String, Value>) {
+    for (key, max) in [
+        ("telegramThreads", MAX_THREADS),
+        ("chromeThreads", MAX_THREADS),
+        ("telegramWatchdogRetries", MAX_WATCHDOG_RESTARTS),
+    ] {
+        let Some(raw) = doc.remove(key) else {
+            continue;
+        };
+        match value_as_u32(&raw) {
+            Some(0) if key != "telegramWatchdogRetries" => {}
+            Some(n) => {
+                doc.insert(key.to_string(), Value::from(n.min(max)));
+            }
+            None if is_blank(&raw) => {}
+            None => println!("[LOG] Settings migration: dropping invalid {} value {}", key, raw),
+        }
+    }
+    for key in ["telegramWatchdog", "telegramWatchdogResendLink"] {
+        let Some(raw) = doc.remove(key) else {
+            continue;
+        };
+        match value_as_bool(&raw) {
+            Some(flag) => {
+                doc.insert(key.to_string(), Value::Bool(flag));
+            }
+            None if is_blank(&raw) => {}
+            None => println!("[LOG] Settings migration: dropping invalid {} value {}", key, raw),
+        }
+    }
+    if let Some(raw) = doc.remove("telegramLaunchSpeed") {
+        let speed = raw.as_str().and_then(LaunchSpeed::parse).unwrap_or_default();
+        doc.insert(
+            "telegramLaunchSpeed".to_string(),
+            serde_json::to_value(speed).unwrap_or(Value::Null),
+        );
+    }
+}
+
+/// Version 2 had a single farm at the top level; it becomes the `default` workspace.
+fn migrate_v2_workspaces(doc: &mut Map<String, Value>) {
+    let mut workspace = Map::new();
+    workspace.insert("id".to_string(), Value::from(DEFAULT_WORKSPACE_ID));
+    workspace.insert("name".to_string(), Value::from("Default"));
+    for key in [
+        "telegramFolderPath",
+        "telegramThreads",
+        "telegramLaunchSpeed",
+        "chromeFolderPath",
+        "chromeThreads",
+    ] {
+        if let Some(value) = doc.remove(key) {
+            workspace.insert(key.to_string(), value);
+        }
+    }
+    doc.insert("workspaces".to_string(), Value::Array(vec![Value::Object(workspace)]));
+    doc.insert("activeWorkspace".to_string(), Value::from(DEFAULT_WORKSPACE_ID));
+}
+
+/// Runs every migration newer than the document's `version` and stamps the current one.
+/// Returns whether anything changed.
+fn migrate(doc: &mut Map<String, Value>) -> Result<bool, String> {
+    let version = match doc.get("version") {
+        None => 1,
+        Some(value) => value
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| format!("invalid version {}", value))?,
+    };
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "settings version {} is newer than this app supports ({})",
+            version, SETTINGS_VERSION
+        ));
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        println!("[LOG] Migrating settings from version {} to {}", from + 1, from + 2);
+        migration(doc);
+    }
+    doc.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+    Ok(version != SETTINGS_VERSION)
+}
+
+fn parse_document(content: &str) -> Result<(AppSettings, bool), String> {
+    let mut doc = match serde_json::from_str::<Value>(content).map_err(|e| e.to_string())? {
+        Value::Object(doc) => doc,
+        other => return Err(format!("expected an object, found {}", other)),
+    };
+    let migrated = migrate(&mut doc)?;
+    let settings: AppSettings = serde_json::from_value(Value::Object(doc)).map_err(|e| e.to_string())?;
+    settings.validate()?;
+    Ok((settings, migrated))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "settings.json".to_string());
+    path.with_file_name(format!("{}.corrupt-{}", name, stamp))
+}
+
+/// Reads and migrates the settings file; `Ok(None)` when it does not exist.
+fn read(path: &Path) -> Result<Option<(AppSettings, bool)>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_document(&content).map(Some),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read settings {}: {}", path.display(), e)),
+    }
+}
+
+/// Loads, migrates and validates the settings file. A missing file yields defaults; an
+/// invalid one is renamed aside (`settings.json.corrupt-<time>`) so it can be inspected,
+/// and defaults are used.
+pub fn load(path: &Path) -> AppSettings {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return AppSettings::default(),
+        Err(e) => {
+            println!("[LOG] Failed to read settings {}: {}", path.display(), e);
+            return AppSettings::default();
+        }
+    };
+
+    match parse_document(&content) {
+        Ok((settings, migrated)) => {
+            if migrated {
+                if let Err(e) = save(path, &settings) {
+                    println!("[LOG] Failed to write migrated settings: {}", e);
+                }
+            }
+            settings
+        }
+        Err(e) => {
+            let backup = backup_path(path);
+            match fs::rename(path, &backup) {
+                Ok(()) => println!(
+                    "[LOG] Settings file is invalid ({}); moved it to {} and using defaults",
+                    e,
+                    backup.display()
+                ),
+                Err(rename_err) => println!(
+                    "[LOG] Settings file is invalid ({}) and could not be backed up: {}",
+                    e, rename_err
+                ),
+            }
+            AppSettings::default()
+        }
+    }
+}
+
+pub fn save(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    settings.validate()?;
+    let body = serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    store::write_atomic(path, body.as_bytes(), "settings")
+}
+
+/// Size and mtime of the file as last seen, to tell external edits from our own writes.
+type Fingerprint = Option<(SystemTime, u64)>;
+
+fn fingerprint(path: &Path) -> Fingerprint {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+struct StoreState {
+    settings: AppSettings,
+    seen: Fingerprint,
+}
+
+/// In-memory settings shared by every command; all writes go through `update`.
+pub struct SettingsStore {
+    path: PathBuf,
+    state: Mutex<StoreState>,
+}
+
+impl SettingsStore {
+    pub fn open(path: PathBuf) -> Self {
+        let settings = load(&path);
+        let seen = fingerprint(&path);
+        Self {
+            path,
+            state: Mutex::new(StoreState { settings, seen }),
+        }
+    }
+
+    /// The current settings. A panic while the lock was held cannot leave them half-written,
+    /// since `update` only publishes validated copies, so a poisoned lock is recovered.
+    pub fn get(&self) -> AppSettings {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .settings
+            .clone()
+    }
+
+    /// Applies `mutate` to a copy, validates and persists it, and only then publishes it.
+    pub fn update(
+        &self,
+        mutate: impl FnOnce(&mut AppSettings) -> Result<(), String>,
+    ) -> Result<AppSettings, String> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| "Settings lock is poisoned".to_string())?;
+        let mut next = state.settings.clone();
+        mutate(&mut next)?;
+        save(&self.path, &next)?;
+        state.seen = fingerprint(&self.path);
+        state.settings = next.clone();
+        Ok(next)
+    }
+
+    /// Picks up edits made to the file outside the app. Invalid edits are logged and ignored
+    /// so a half-saved file in an editor does not reset anything.
+    pub fn reload_if_changed(&self) -> Option<AppSettings> {
+        let mut state = self.state.lock().ok()?;
+        let current = fingerprint(&self.path);
+        if current == state.seen {
+            return None;
+        }
+        state.seen = current;
+        match read(&self.path) {
+            Ok(Some((settings, _))) => {
+                println!("[LOG] Settings file changed on disk, reloaded");
+                state.settings = settings.clone();
+                Some(settings)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                println!("[LOG] Ignoring external settings edit: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("settings-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn document(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(doc) => doc,
+            other => panic!("not an object: {}", other),
+        }
+    }
+
+    /// A settings file as version 1 wrote it: no `version`, every value a string.
+    fn v1_fixture() -> Value {
+        json!({
+            "telegramFolderPath": "C:\\Farm\\TG",
+            "telegramThreads": "4",
+            "chromeFolderPath": "",
+            "chromeThreads": "0",
+            "telegramWatchdog": "on",
+            "telegramWatchdogRetries": "99",
+            "telegramLaunchSpeed": "turbo",
+            "theme": "dark"
+        })
+    }
+
+    #[test]
+    fn v1_string_fields_become_typed() {
+        let mut doc = document(v1_fixture());
+        migrate_v1_string_fields(&mut doc);
+        assert_eq!(doc["telegramThreads"], json!(4));
+        assert!(doc.get("chromeThreads").is_none(), "0 threads falls back to the default");
+        assert_eq!(doc["telegramWatchdog"], json!(true));
+        assert_eq!(doc["telegramWatchdogRetries"], json!(MAX_WATCHDOG_RESTARTS));
+        assert_eq!(doc["telegramLaunchSpeed"], json!("conservative"));
+        assert_eq!(doc["theme"], json!("dark"));
+    }
+
+    #[test]
+    fn v1_document_migrates_to_current_version() {
+        let (settings, migrated) = parse_document(&v1_fixture().to_string()).unwrap();
+        assert!(migrated);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.active_workspace, DEFAULT_WORKSPACE_ID);
+        assert_eq!(settings.workspaces.len(), 1);
+        let workspace = settings.active();
+        assert_eq!(workspace.id, DEFAULT_WORKSPACE_ID);
+        assert_eq!(workspace.telegram_folder_path, "C:\\Farm\\TG");
+        assert_eq!(workspace.telegram_threads, 4);
+        assert_eq!(workspace.chrome_threads, 1);
+        assert_eq!(workspace.telegram_launch_speed, LaunchSpeed::Conservative);
+        assert!(settings.telegram_watchdog);
+        assert_eq!(settings.telegram_watchdog_retries, MAX_WATCHDOG_RESTARTS);
+        assert!(settings.telegram_watchdog_resend_link);
+    }
+
+    #[test]
+    fn migrate_checks_the_version() {
+        let mut current = document(serde_json::to_value(AppSettings::default()).unwrap());
+        let before = current.clone();
+        assert_eq!(migrate(&mut current), Ok(false));
+        assert_eq!(current, before);
+
+        let mut v2 = document(json!({ "version": 2, "telegramThreads": 3 }));
+        assert_eq!(migrate(&mut v2), Ok(true));
+        assert_eq!(v2["workspaces"][0]["telegramThreads"], json!(3));
+        assert!(v2.get("telegramThreads").is_none());
+
+        assert!(migrate(&mut document(json!({ "version": SETTINGS_VERSION + 1 }))).is_err());
+        assert!(migrate(&mut document(json!({ "version": 0 }))).is_err());
+        assert!(migrate(&mut document(json!({ "version": "3" }))).is_err());
+    }
+
+    #[test]
+    fn apply_patch_accepts_strings_and_blanks() {
+        let mut settings = AppSettings::default();
+        settings
+            .apply_patch(&json!({
+                "telegramThreads": "8",
+                "chromeThreads": 2,
+                "telegramWatchdog": "yes",
+                "telegramLaunchSpeed": "fast",
+                "telegramFolderPath": "  D:\\TG  ",
+                "theme": "light"
+            }))
+            .unwrap();
+        let workspace = settings.active();
+        assert_eq!(workspace.telegram_threads, 8);
+        assert_eq!(workspace.chrome_threads, 2);
+        assert_eq!(workspace.telegram_launch_speed, LaunchSpeed::Fast);
+        assert_eq!(workspace.telegram_folder_path, "D:\\TG");
+        assert!(settings.telegram_watchdog);
+
+        settings
+            .apply_patch(&json!({ "telegramThreads": "", "telegramLaunchSpeed": "" }))
+            .unwrap();
+        assert_eq!(settings.active().telegram_threads, 1);
+        assert_eq!(settings.active().telegram_launch_speed, LaunchSpeed::Conservative);
+    }
+
+    #[test]
+    fn apply_patch_reports_every_invalid_key() {
+        let mut settings = AppSettings::default();
+        let error = settings
+            .apply_patch(&json!({
+                "telegramThreads": "many",
+                "telegramWatchdog": "maybe",
+                "chromeLaunchSpeed": "warp"
+            }))
+            .unwrap_err();
+        assert!(error.contains("telegramThreads"), "{}", error);
+        assert!(error.contains("telegramWatchdog"), "{}", error);
+        assert!(error.contains("chromeLaunchSpeed"), "{}", error);
+
+        let error = settings.apply_patch(&json!({ "chromeThreads": 500 })).unwrap_err();
+        assert!(error.contains("chromeThreads"), "{}", error);
+    }
+
+    #[test]
+    fn load_rewrites_migrated_files() {
+        let dir = temp_dir();
+        let path = dir.join("settings.json");
+        fs::write(&path, v1_fixture().to_string()).unwrap();
+
+        let settings = load(&path);
+        assert_eq!(settings.active().telegram_threads, 4);
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], json!(SETTINGS_VERSION));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn corrupt_file_is_backed_up_and_defaults_are_used() {
+        let dir = temp_dir();
+        let path = dir.join("settings.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        let settings = load(&path);
+        let defaults = AppSettings::default();
+        assert_eq!(settings.active().telegram_threads, defaults.active().telegram_threads);
+        assert!(!path.exists());
+        let backups: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with("settings.json.corrupt-"), "{:?}", backups);
+        assert_eq!(fs::read_to_string(dir.join(&backups[0])).unwrap(), "{ not json");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn get_survives_a_poisoned_lock() {
+        let dir = temp_dir();
+        let store = std::sync::Arc::new(SettingsStore::open(dir.join("settings.json")));
+        store
+            .update(|settings| settings.apply_patch(&json!({ "telegramThreads": 6 })))
+            .unwrap();
+        let poisoner = store.clone();
+        let panicked = std::thread::spawn(move || {
+            let _ = poisoner.update(|_| panic!("mutation failed"));
+        })
+        .join();
+        assert!(panicked.is_err());
+        assert_eq!(store.get().active().telegram_threads, 6);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn apply_patch_can_edit_another_workspace_without_switching() {
+        let mut settings = AppSettings::default();
+        settings
+            .upsert_workspace(Workspace {
+                id: "second".to_string(),
+                name: "Second".to_string(),
+                ..Workspace::default()
+            })
+            .unwrap();
+        settings
+            .apply_patch(&json!({ "workspace": "Second", "telegramThreads": 5 }))
+            .unwrap();
+        assert_eq!(settings.active_workspace, DEFAULT_WORKSPACE_ID);
+        assert_eq!(settings.active().telegram_threads, 1);
+        assert_eq!(settings.workspace(Some("second")).unwrap().telegram_threads, 5);
+
+        settings.apply_patch(&json!({ "activeWorkspace": "second" })).unwrap();
+        assert_eq!(settings.active_workspace, DEFAULT_WORKSPACE_ID);
+        assert!(settings.apply_patch(&json!({ "workspace": "missing" })).is_err());
+    }
+
+    #[test]
+    fn jitter_stays_in_range() {
+        let none = TimingProfile::default();
+        assert_eq!(none.jittered(500), 500);
+
+        let timing = TimingProfile {
+            jitter_min_ms: 100,
+            jitter_max_ms: 250,
+            ..TimingProfile::default()
+        };
+        for _ in 0..200 {
+            let delay = timing.jittered(1000);
+            assert!((1100..=1250).contains(&delay), "{}", delay);
+        }
+        let fixed = TimingProfile {
+            jitter_min_ms: 40,
+            jitter_max_ms: 40,
+            ..TimingProfile::default()
+        };
+        assert_eq!(fixed.jitter_ms(), 40);
+    }
+
+    #[test]
+    fn timing_validation() {
+        let mut errors = Vec::new();
+        LaunchSpeed::Fast.telegram_preset().unwrap().validate("preset", &mut errors);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let timing = TimingProfile {
+            spawn_delay_ms: MAX_TIMING_MS + 1,
+            jitter_min_ms: 300,
+            jitter_max_ms: 200,
+            ..TimingProfile::default()
+        };
+        timing.validate("custom", &mut errors);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("custom.spawnDelayMs"), "{}", errors[0]);
+        assert!(errors[1].contains("jitterMinMs"), "{}", errors[1]);
+
+        let mut settings = AppSettings::default();
+        let error = settings
+            .apply_patch(&json!({ "chromeTiming": { "linkDelayMs": MAX_TIMING_MS + 1 } }))
+            .unwrap_err();
+        assert!(error.contains("chromeTiming.linkDelayMs"), "{}", error);
+    }
+
+    #[test]
+    fn batch_gate_opens_every_batch() {
+        let timing = TimingProfile {
+            batch_cooldown_ms: 5000,
+            ..TimingProfile::default()
+        };
+        let gates: Vec<Option<u64>> = (0..7).map(|launched| timing.cooldown_before(launched, 3)).collect();
+        assert_eq!(gates, [None, None, None, Some(5000), None, None, Some(5000)]);
+        assert_eq!(timing.cooldown_before(1, 0), Some(5000));
+        assert_eq!(TimingProfile::default().cooldown_before(3, 3), None);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Reads a JSON document, treating a missing file as the default value.
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path, what: &str) -> Result<T, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", what, e)),
+    };
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", what, e))
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T, what: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {} directory: {}", what, e))?;
+    }
+    let body = serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize {}: {}", what, e))?;
+    fs::write(path, body).map_err(|e| format!("Failed to write {}: {}", what, e))
+}
+
+/// Writes `path` via a sibling temp file that is fsynced and then renamed over the target,
+/// so readers never observe a half-written file.
+pub fn write_atomic(path: &Path, body: &[u8], what: &str) -> Result<(), String> {
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+    if let Some(parent) = parent {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {} directory: {}", what, e))?;
+    }
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid {} path: {}", what, path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(body)?;
+        file.sync_all()
+    };
+    if let Err(e) = write_tmp() {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Failed to write {}: {}", what, e));
+    }
+    if let Err(e) = fs::rename(&tmp_path, path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Failed to replace {}: {}", what, e));
+    }
+    // Persist the rename itself; directories cannot be opened for syncing on Windows.
+    #[cfg(unix)]
+    if let Some(parent) = parent {
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
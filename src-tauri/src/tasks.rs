@@ -0,0 +1,378 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::store;
+
+static TASKS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Recurrence {
+    Daily,
+    Weekdays,
+    EveryHours { hours: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyTask {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub recurrence: Recurrence,
+    /// Local wall-clock time, `HH:MM`. For `EveryHours` it anchors the first run.
+    pub due_time: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default)]
+    pub last_completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_notified_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub next_due_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyTaskInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub recurrence: Recurrence,
+    pub due_time: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct TaskStore {
+    #[serde(default)]
+    pub tasks: Vec<DailyTask>,
+}
+
+pub fn parse_due_time(raw: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(raw.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid due time (expected HH:MM): {}", raw))
+}
+
+fn validate_recurrence(recurrence: &Recurrence) -> Result<(), String> {
+    if let Recurrence::EveryHours { hours } = recurrence {
+        if *hours == 0 || *hours > 24 * 7 {
+            return Err(format!("Interval must be between 1 and 168 hours, got {}", hours));
+        }
+    }
+    Ok(())
+}
+
+fn local_at(date: NaiveDate, time: NaiveTime) -> DateTime<Local> {
+    let naive = date.and_time(time);
+    // A DST gap has no valid local time; shift into the hour after it.
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| Local.from_local_datetime(&(naive + Duration::hours(1))).earliest())
+        .unwrap_or_else(|| Local.from_utc_datetime(&naive))
+}
+
+/// First occurrence strictly after `after`.
+pub fn next_occurrence(
+    recurrence: &Recurrence,
+    due_time: NaiveTime,
+    anchor_date: NaiveDate,
+    after: DateTime<Local>,
+) -> DateTime<Local> {
+    match recurrence {
+        Recurrence::Daily => {
+            let candidate = local_at(after.date_naive(), due_time);
+            if candidate > after {
+                candidate
+            } else {
+                local_at(after.date_naive() + Duration::days(1), due_time)
+            }
+        }
+        Recurrence::Weekdays => {
+            let mut date = after.date_naive();
+            loop {
+                let candidate = local_at(date, due_time);
+                let is_weekday = !matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+                if is_weekday && candidate > after {
+                    return candidate;
+                }
+                date += Duration::days(1);
+            }
+        }
+        Recurrence::EveryHours { hours } => {
+            let anchor = local_at(anchor_date, due_time);
+            if anchor > after {
+                return anchor;
+            }
+            let step = Duration::hours(i64::from((*hours).max(1)));
+            let elapsed = after.signed_duration_since(anchor);
+            let steps = elapsed.num_seconds() / step.num_seconds() + 1;
+            anchor + step * steps as i32
+        }
+    }
+}
+
+impl DailyTask {
+    pub fn from_input(input: DailyTaskInput, now: DateTime<Utc>) -> Result<Self, String> {
+        let mut task = DailyTask {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: String::new(),
+            description: String::new(),
+            recurrence: Recurrence::Daily,
+            due_time: String::new(),
+            enabled: true,
+            completed: false,
+            last_completed_at: None,
+            last_notified_at: None,
+            next_due_at: None,
+            created_at: now,
+        };
+        task.apply_input(input, now)?;
+        Ok(task)
+    }
+
+    pub fn apply_input(&mut self, input: DailyTaskInput, now: DateTime<Utc>) -> Result<(), String> {
+        let title = input.title.trim().to_string();
+        if title.is_empty() {
+            return Err("Task title is required".to_string());
+        }
+        let due_time = parse_due_time(&input.due_time)?;
+        validate_recurrence(&input.recurrence)?;
+
+        self.title = title;
+        self.description = input.description;
+        self.recurrence = input.recurrence;
+        self.due_time = due_time.format("%H:%M").to_string();
+        self.enabled = input.enabled;
+        self.reschedule(now);
+        self.refresh_completed(now);
+        Ok(())
+    }
+
+    /// Start of the window in which a completion counts for `occurrence`:
+    /// the local day for daily/weekday tasks, the preceding interval otherwise.
+    fn completion_window_start(&self, occurrence: DateTime<Utc>) -> DateTime<Utc> {
+        match self.recurrence {
+            Recurrence::EveryHours { hours } => occurrence - Duration::hours(i64::from(hours.max(1))),
+            Recurrence::Daily | Recurrence::Weekdays => {
+                let day = occurrence.with_timezone(&Local).date_naive();
+                local_at(day, NaiveTime::MIN).with_timezone(&Utc)
+            }
+        }
+    }
+
+    fn is_done_for(&self, occurrence: DateTime<Utc>) -> bool {
+        let window_start = self.completion_window_start(occurrence);
+        self.last_completed_at
+            .map(|completed_at| completed_at >= window_start)
+            .unwrap_or(false)
+    }
+
+    pub fn set_completed(&mut self, completed: bool, now: DateTime<Utc>) {
+        self.last_completed_at = if completed { Some(now) } else { None };
+        self.refresh_completed(now);
+    }
+
+    /// Recomputes the `completed` flag shown to the webview for the current period.
+    pub fn refresh_completed(&mut self, now: DateTime<Utc>) {
+        self.completed = match self.recurrence {
+            Recurrence::Daily | Recurrence::Weekdays => self.is_done_for(now),
+            Recurrence::EveryHours { .. } => self
+                .next_due_at
+                .map(|next_due_at| self.is_done_for(next_due_at))
+                .unwrap_or(false),
+        };
+    }
+
+    pub fn reschedule(&mut self, now: DateTime<Utc>) {
+        let Ok(due_time) = parse_due_time(&self.due_time) else {
+            self.next_due_at = None;
+            return;
+        };
+        let anchor_date = self.created_at.with_timezone(&Local).date_naive();
+        let next = next_occurrence(&self.recurrence, due_time, anchor_date, now.with_timezone(&Local));
+        self.next_due_at = Some(next.with_timezone(&Utc));
+    }
+}
+
+impl TaskStore {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        store::load_json(path, "daily tasks")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        store::save_json(path, self, "daily tasks")
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut DailyTask> {
+        self.tasks.iter_mut().find(|task| task.id == id)
+    }
+
+    /// Advances every task whose due time has passed and returns the ones that should remind.
+    /// Tasks already completed for the passed occurrence are advanced silently.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<DailyTask> {
+        let mut due = Vec::new();
+        for task in self.tasks.iter_mut().filter(|task| task.enabled) {
+            let Some(next_due_at) = task.next_due_at else {
+                task.reschedule(now);
+                continue;
+            };
+            if next_due_at > now {
+                continue;
+            }
+
+            if !task.is_done_for(next_due_at) {
+                task.last_notified_at = Some(now);
+                due.push(task.clone());
+            }
+            task.reschedule(now);
+            task.refresh_completed(now);
+        }
+        due
+    }
+}
+
+/// Runs a load/mutate/save cycle on the task file under a process-wide lock.
+/// The file is only rewritten when `mutate` changed something, so idle ticks stay read-only.
+pub fn with_tasks<T>(
+    path: &Path,
+    mutate: impl FnOnce(&mut TaskStore) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = TASKS_LOCK
+        .lock()
+        .map_err(|_| "Daily task lock is poisoned".to_string())?;
+    let mut tasks = TaskStore::load(path)?;
+    let loaded = tasks.clone();
+    let result = mutate(&mut tasks)?;
+    if tasks != loaded {
+        tasks.save(path)?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(raw: &str) -> NaiveTime {
+        parse_due_time(raw).unwrap()
+    }
+
+    /// 2024-01-05 is a Friday; no DST change happens around it in any zone we run in.
+    fn friday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()
+    }
+
+    fn task(recurrence: Recurrence, due_time: &str, now: DateTime<Utc>) -> DailyTask {
+        let input = DailyTaskInput {
+            id: None,
+            title: "Claim rewards".to_string(),
+            description: String::new(),
+            recurrence,
+            due_time: due_time.to_string(),
+            enabled: true,
+        };
+        DailyTask::from_input(input, now).unwrap()
+    }
+
+    #[test]
+    fn due_time_must_be_hh_mm() {
+        assert_eq!(time("09:30"), NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+        assert!(parse_due_time("9.30").is_err());
+        assert!(parse_due_time("25:00").is_err());
+    }
+
+    #[test]
+    fn daily_runs_today_until_the_due_time_passes() {
+        let due = time("10:00");
+        let before = local_at(friday(), time("09:00"));
+        let after = local_at(friday(), time("10:00"));
+        assert_eq!(next_occurrence(&Recurrence::Daily, due, friday(), before), local_at(friday(), due));
+        assert_eq!(
+            next_occurrence(&Recurrence::Daily, due, friday(), after),
+            local_at(friday() + Duration::days(1), due)
+        );
+    }
+
+    #[test]
+    fn weekdays_skip_the_weekend() {
+        let due = time("10:00");
+        let friday_evening = local_at(friday(), time("18:00"));
+        let monday = friday() + Duration::days(3);
+        assert_eq!(monday.weekday(), Weekday::Mon);
+        assert_eq!(
+            next_occurrence(&Recurrence::Weekdays, due, friday(), friday_evening),
+            local_at(monday, due)
+        );
+    }
+
+    #[test]
+    fn every_hours_steps_from_the_anchor() {
+        let recurrence = Recurrence::EveryHours { hours: 6 };
+        let due = time("08:00");
+        let anchor = local_at(friday(), due);
+        assert_eq!(next_occurrence(&recurrence, due, friday(), anchor - Duration::hours(1)), anchor);
+        assert_eq!(next_occurrence(&recurrence, due, friday(), anchor), anchor + Duration::hours(6));
+        assert_eq!(
+            next_occurrence(&recurrence, due, friday(), anchor + Duration::hours(13)),
+            anchor + Duration::hours(18)
+        );
+    }
+
+    #[test]
+    fn interval_is_bounded() {
+        assert!(validate_recurrence(&Recurrence::EveryHours { hours: 0 }).is_err());
+        assert!(validate_recurrence(&Recurrence::EveryHours { hours: 169 }).is_err());
+        assert!(validate_recurrence(&Recurrence::EveryHours { hours: 168 }).is_ok());
+    }
+
+    #[test]
+    fn take_due_reminds_once_and_reschedules() {
+        let created = local_at(friday(), time("08:00")).with_timezone(&Utc);
+        let mut store = TaskStore {
+            tasks: vec![task(Recurrence::Daily, "10:00", created)],
+        };
+        let due_at = local_at(friday(), time("10:00")).with_timezone(&Utc);
+        assert_eq!(store.tasks[0].next_due_at, Some(due_at));
+
+        let before_due = store.clone();
+        assert!(store.take_due(due_at - Duration::minutes(1)).is_empty());
+        assert_eq!(store, before_due, "an idle tick must not change the store");
+
+        let now = due_at + Duration::minutes(1);
+        let due = store.take_due(now);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].last_notified_at, Some(now));
+        assert_eq!(store.tasks[0].next_due_at, Some(due_at + Duration::days(1)));
+        assert!(store.take_due(now).is_empty());
+    }
+
+    #[test]
+    fn completed_tasks_advance_silently() {
+        let created = local_at(friday(), time("08:00")).with_timezone(&Utc);
+        let mut store = TaskStore {
+            tasks: vec![task(Recurrence::Daily, "10:00", created)],
+        };
+        store.tasks[0].set_completed(true, created + Duration::minutes(30));
+        assert!(store.tasks[0].completed);
+
+        let due_at = local_at(friday(), time("10:00")).with_timezone(&Utc);
+        assert!(store.take_due(due_at + Duration::minutes(1)).is_empty());
+        assert_eq!(store.tasks[0].next_due_at, Some(due_at + Duration::days(1)));
+    }
+}
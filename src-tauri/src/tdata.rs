@@ -0,0 +1,350 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Every file Telegram Desktop writes through its storage layer starts with this magic.
+const TDF_MAGIC: &[u8; 4] = b"TDF$";
+/// Magic, version and the trailing MD5 checksum.
+const TDF_MIN_LEN: u64 = 4 + 4 + 16;
+/// Telegram writes `<name>s`; older builds alternated between `<name>1` and `<name>0`.
+const TDF_SUFFIXES: [char; 3] = ['s', '1', '0'];
+/// Per-account files and folders are named after 16 hex digits derived from the account key.
+const ACCOUNT_ID_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TdataHealth {
+    /// One account with a valid authorization file.
+    Authorized,
+    /// Valid `key_datas` but no authorized account left.
+    LoggedOut,
+    /// A storage file is truncated, has a bad header, or `key_datas` is missing next to account data.
+    Corrupt,
+    /// No `tdata` folder, or nothing Telegram ever wrote to it.
+    Empty,
+    /// More than one authorized account in the same `tdata`.
+    MultiAccount,
+}
+
+impl TdataHealth {
+    pub fn is_authorized(self) -> bool {
+        matches!(self, TdataHealth::Authorized | TdataHealth::MultiAccount)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TdataReport {
+    pub health: TdataHealth,
+    /// Ids (`D877F783D5D3EF8C` style) of the accounts with a valid authorization file.
+    pub accounts: Vec<String>,
+    /// The evidence line that kept the folder from counting as authorized.
+    pub reason: Option<String>,
+    /// One line per file or folder that decided the classification.
+    pub evidence: Vec<String>,
+}
+
+/// Header of a storage file; the checksum is not verified.
+#[derive(Debug, Clone, Copy)]
+struct TdfInfo {
+    version: u32,
+    len: u64,
+}
+
+fn read_tdf(path: &Path) -> Result<TdfInfo, String> {
+    let len = fs::metadata(path)
+        .map_err(|e| format!("unreadable ({})", e))?
+        .len();
+    if len < TDF_MIN_LEN {
+        return Err(format!("truncated ({} bytes)", len));
+    }
+    let mut header = [0u8; 8];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|e| format!("unreadable ({})", e))?;
+    if &header[..4] != TDF_MAGIC {
+        return Err("missing TDF$ header".to_string());
+    }
+    let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    Ok(TdfInfo { version, len })
+}
+
+/// The on-disk file for storage name `base`, trying each suffix Telegram may have used.
+fn find_tdf(dir: &Path, base: &str) -> Option<PathBuf> {
+    TDF_SUFFIXES
+        .iter()
+        .map(|suffix| dir.join(format!("{}{}", base, suffix)))
+        .find(|path| path.is_file())
+}
+
+fn is_account_id(name: &str) -> bool {
+    name.len() == ACCOUNT_ID_LEN && name.chars().all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c))
+}
+
+/// `D877F783D5D3EF8C` for the folder and for `D877F783D5D3EF8Cs` and its older variants.
+fn account_id_of(name: &str) -> Option<&str> {
+    if is_account_id(name) {
+        return Some(name);
+    }
+    let stem = name.strip_suffix(TDF_SUFFIXES)?;
+    is_account_id(stem).then_some(stem)
+}
+
+/// Checks one storage file and records the outcome; `Ok(None)` means it does not exist.
+fn check_tdf(dir: &Path, base: &str, label: &str, evidence: &mut Vec<String>) -> Result<Option<TdfInfo>, ()> {
+    let Some(path) = find_tdf(dir, base) else {
+        return Ok(None);
+    };
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match read_tdf(&path) {
+        Ok(info) => {
+            evidence.push(format!("{}: {} valid (v{}, {} bytes)", label, name, info.version, info.len));
+            Ok(Some(info))
+        }
+        Err(e) => {
+            evidence.push(format!("{}: {} {}", label, name, e));
+            Err(())
+        }
+    }
+}
+
+/// Classifies a `tdata` folder from the Telegram Desktop storage layout: `key_datas` holds the
+/// local encryption key, each account has a `<id>s` authorization file and a `<id>/maps` index,
+/// and `settingss` holds app settings.
+pub fn inspect(tdata: &Path) -> TdataReport {
+    let mut evidence = Vec::new();
+    // Early verdicts: no account was checked and the last evidence line is the reason.
+    let report = |health, evidence: Vec<String>| TdataReport {
+        health,
+        accounts: Vec::new(),
+        reason: evidence.last().cloned(),
+        evidence,
+    };
+
+    let entries: Vec<String> = match fs::read_dir(tdata) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        Err(e) => {
+            evidence.push(format!("tdata: not readable ({})", e));
+            return report(TdataHealth::Empty, evidence);
+        }
+    };
+    if entries.is_empty() {
+        evidence.push("tdata: folder is empty".to_string());
+        return report(TdataHealth::Empty, evidence);
+    }
+
+    let mut account_ids: Vec<&str> = entries
+        .iter()
+        .filter_map(|name| account_id_of(name))
+        .collect();
+    account_ids.sort_unstable();
+    account_ids.dedup();
+
+    match check_tdf(tdata, "key_data", "key", &mut evidence) {
+        Ok(Some(_)) => {}
+        Ok(None) if account_ids.is_empty() => {
+            evidence.push("key: no key_datas, Telegram never saved a session here".to_string());
+            return report(TdataHealth::Empty, evidence);
+        }
+        Ok(None) => {
+            evidence.push(format!("key: key_datas missing next to {} account folder(s)", account_ids.len()));
+            return report(TdataHealth::Corrupt, evidence);
+        }
+        Err(()) => return report(TdataHealth::Corrupt, evidence),
+    }
+    if let Ok(None) = check_tdf(tdata, "settings", "settings", &mut evidence) {
+        evidence.push("settings: no settingss file".to_string());
+    }
+
+    // First broken account file; one is enough to call the folder corrupt.
+    let mut corrupt: Option<String> = None;
+    let mut authorized = Vec::new();
+    for id in account_ids {
+        let label = format!("account {}", id);
+        let folder = tdata.join(id);
+        match check_tdf(tdata, id, &label, &mut evidence) {
+            Ok(Some(_)) => {
+                authorized.push(id.to_string());
+                if !folder.is_dir() {
+                    evidence.push(format!("{}: no map folder", label));
+                    continue;
+                }
+                match check_tdf(&folder, "map", &label, &mut evidence) {
+                    Ok(Some(_)) => {}
+                    Ok(None) => evidence.push(format!("{}: map folder has no maps file", label)),
+                    Err(()) => {
+                        corrupt.get_or_insert_with(|| evidence.last().cloned().unwrap_or_default());
+                    }
+                }
+            }
+            Ok(None) => evidence.push(format!("{}: folder left without an authorization file", label)),
+            Err(()) => {
+                corrupt.get_or_insert_with(|| evidence.last().cloned().unwrap_or_default());
+            }
+        }
+    }
+
+    let (health, reason) = match (corrupt, authorized.len()) {
+        (Some(line), _) => (TdataHealth::Corrupt, Some(line)),
+        (None, 0) => {
+            let line = "no authorized account".to_string();
+            evidence.push(line.clone());
+            (TdataHealth::LoggedOut, Some(line))
+        }
+        (None, 1) => (TdataHealth::Authorized, None),
+        (None, _) => (TdataHealth::MultiAccount, None),
+    };
+    TdataReport {
+        health,
+        accounts: authorized,
+        reason,
+        evidence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "D877F783D5D3EF8C";
+    const SECOND: &str = "A7FDF864FBC10B77";
+
+    /// A scratch `tdata` folder under the system temp dir, removed on drop.
+    struct TempTdata {
+        root: PathBuf,
+    }
+
+    impl TempTdata {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("abuseapp-tdata-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(root.join("tdata")).unwrap();
+            Self { root }
+        }
+
+        fn tdata(&self) -> PathBuf {
+            self.root.join("tdata")
+        }
+
+        fn write(&self, relative: &str, body: &[u8]) -> &Self {
+            let path = self.tdata().join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, body).unwrap();
+            self
+        }
+
+        fn tdf(&self, relative: &str) -> &Self {
+            let mut body = TDF_MAGIC.to_vec();
+            body.extend_from_slice(&4_003_001u32.to_le_bytes());
+            body.extend_from_slice(&[7u8; 64]);
+            body.extend_from_slice(&[0u8; 16]);
+            self.write(relative, &body)
+        }
+
+        fn account(&self, id: &str) -> &Self {
+            self.tdf(&format!("{}s", id)).tdf(&format!("{}/maps", id))
+        }
+    }
+
+    impl Drop for TempTdata {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn missing_and_empty_folders_are_empty() {
+        let dir = TempTdata::new();
+        assert_eq!(inspect(&dir.root.join("nope")).health, TdataHealth::Empty);
+        assert_eq!(inspect(&dir.tdata()).health, TdataHealth::Empty);
+
+        dir.write("emoji/1.webp", b"x").write("working", b"");
+        let report = inspect(&dir.tdata());
+        assert_eq!(report.health, TdataHealth::Empty);
+        assert!(report.evidence.iter().any(|line| line.contains("no key_datas")));
+    }
+
+    #[test]
+    fn single_account_is_authorized() {
+        let dir = TempTdata::new();
+        dir.tdf("key_datas").tdf("settingss").account(MAIN);
+        let report = inspect(&dir.tdata());
+        assert_eq!(report.health, TdataHealth::Authorized);
+        assert_eq!(report.accounts, vec![MAIN.to_string()]);
+        assert_eq!(report.reason, None);
+        assert!(report.evidence.iter().any(|line| line.contains("key_datas valid (v4003001")));
+    }
+
+    #[test]
+    fn older_numeric_suffixes_are_recognised() {
+        let dir = TempTdata::new();
+        dir.tdf("key_data1").tdf("settings0").tdf(&format!("{}0", MAIN)).tdf(&format!("{}/map1", MAIN));
+        let report = inspect(&dir.tdata());
+        assert_eq!(report.health, TdataHealth::Authorized);
+        assert_eq!(report.accounts, vec![MAIN.to_string()]);
+    }
+
+    #[test]
+    fn key_without_accounts_is_logged_out() {
+        let dir = TempTdata::new();
+        dir.tdf("key_datas").tdf("settingss").tdf(&format!("{}/maps", MAIN));
+        let report = inspect(&dir.tdata());
+        assert_eq!(report.health, TdataHealth::LoggedOut);
+        assert!(report.accounts.is_empty());
+        assert!(report
+            .evidence
+            .iter()
+            .any(|line| line.contains("left without an authorization file")));
+    }
+
+    #[test]
+    fn two_accounts_are_multi_account() {
+        let dir = TempTdata::new();
+        dir.tdf("key_datas").account(MAIN).account(SECOND);
+        let report = inspect(&dir.tdata());
+        assert_eq!(report.health, TdataHealth::MultiAccount);
+        assert_eq!(report.accounts, vec![SECOND.to_string(), MAIN.to_string()]);
+        assert!(report.health.is_authorized());
+    }
+
+    #[test]
+    fn broken_files_are_corrupt() {
+        let truncated = TempTdata::new();
+        truncated.write("key_datas", b"TDF$").account(MAIN);
+        let report = inspect(&truncated.tdata());
+        assert_eq!(report.health, TdataHealth::Corrupt);
+        assert!(report.evidence.iter().any(|line| line.contains("truncated (4 bytes)")));
+
+        let bad_header = TempTdata::new();
+        bad_header
+            .tdf("key_datas")
+            .account(MAIN)
+            .write(&format!("{}s", MAIN), &[0u8; 64]);
+        let report = inspect(&bad_header.tdata());
+        assert_eq!(report.health, TdataHealth::Corrupt);
+        assert_eq!(
+            report.reason.as_deref(),
+            Some(format!("account {}: {}s missing TDF$ header", MAIN, MAIN).as_str())
+        );
+
+        let bad_map = TempTdata::new();
+        bad_map.tdf("key_datas").account(MAIN).write(&format!("{}/maps", MAIN), b"junk");
+        assert_eq!(inspect(&bad_map.tdata()).health, TdataHealth::Corrupt);
+    }
+
+    #[test]
+    fn account_data_without_key_is_corrupt() {
+        let dir = TempTdata::new();
+        dir.account(MAIN);
+        let report = inspect(&dir.tdata());
+        assert_eq!(report.health, TdataHealth::Corrupt);
+        assert!(report.evidence.iter().any(|line| line.contains("key_datas missing")));
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+use crate::TelegramLink;
+
+const DEFAULT_RANDOM_LEN: usize = 8;
+const MAX_RANDOM_LEN: usize = 64;
+const PLACEHOLDER_KEYS: [&str; 4] = ["account", "index", "random", "csv"];
+
+/// Per-account values from the CSV named in `TelegramLink.payload_csv`, keyed by profile number.
+pub type AccountColumns = HashMap<i32, HashMap<String, String>>;
+
+/// What a placeholder can see while rendering the link for one profile.
+pub struct TemplateContext<'a> {
+    /// Account number; accounts outside the folder layout run under a negative stand-in id.
+    pub profile: i32,
+    /// 1-based position of the profile in the batch.
+    pub index: usize,
+    pub columns: Option<&'a AccountColumns>,
+    /// Validation-only rendering: `{csv:column}` without a loaded CSV yields the column name.
+    pub preview: bool,
+}
+
+pub fn has_placeholders(link: &TelegramLink) -> bool {
+    link.ref_link.contains('{')
+        || link.message_text.contains('{')
+        || link.steps.iter().any(|step| has_placeholders(&step.link))
+}
+
+/// Splits one CSV line, honouring double-quoted fields with `""` escapes.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields.into_iter().map(|field| field.trim().to_string()).collect()
+}
+
+/// `5`, `TG 5` and `tg5` all identify profile 5.
+fn parse_account_key(raw: &str) -> Option<i32> {
+    let digits = raw.trim().trim_start_matches(|c: char| c.is_ascii_alphabetic()).trim();
+    digits.parse().ok()
+}
+
+/// Reads a header-first CSV. Rows are matched to profiles by an `account` or `profile`
+/// column, falling back to the first column.
+pub fn load_account_columns(path: &Path) -> Result<AccountColumns, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read payload CSV {}: {}", path.display(), e))?;
+    let mut lines = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .filter(|line| !line.trim().is_empty());
+    let header = lines
+        .next()
+        .map(split_csv_line)
+        .ok_or_else(|| format!("Payload CSV {} is empty", path.display()))?;
+    let key_column = header
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case("account") || name.eq_ignore_ascii_case("profile"))
+        .unwrap_or(0);
+
+    let mut columns = AccountColumns::new();
+    for (line_no, line) in lines.enumerate() {
+        let values = split_csv_line(line);
+        let key = values.get(key_column).map(String::as_str).unwrap_or_default();
+        let profile = parse_account_key(key).ok_or_else(|| {
+            format!(
+                "Payload CSV {} line {}: invalid account \"{}\"",
+                path.display(),
+                line_no + 2,
+                key
+            )
+        })?;
+        let row = header
+            .iter()
+            .cloned()
+            .zip(values.into_iter().chain(std::iter::repeat(String::new())))
+            .collect();
+        columns.insert(profile, row);
+    }
+    Ok(columns)
+}
+
+fn random_token(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// The account number, which stand-in ids of unnumbered folders do not have.
+fn account_number(key: &str, ctx: &TemplateContext) -> Result<i32, String> {
+    if ctx.profile <= 0 {
+        return Err(format!("{{{}}} needs a numbered account folder", key));
+    }
+    Ok(ctx.profile)
+}
+
+fn render_placeholder(name: &str, ctx: &TemplateContext) -> Result<String, String> {
+    let (key, arg) = match name.split_once(':') {
+        Some((key, arg)) => (key.trim(), Some(arg.trim())),
+        None => (name.trim(), None),
+    };
+    match (key, arg) {
+        ("account", None) => account_number(name, ctx).map(|profile| profile.to_string()),
+        ("index", None) => Ok(ctx.index.to_string()),
+        ("random", None) => Ok(random_token(DEFAULT_RANDOM_LEN)),
+        ("random", Some(len)) => match len.parse::<usize>() {
+            Ok(len) if (1..=MAX_RANDOM_LEN).contains(&len) => Ok(random_token(len)),
+            _ => Err(format!("{{random:{}}} needs a length between 1 and {}", len, MAX_RANDOM_LEN)),
+        },
+        ("csv", Some(column)) if !column.is_empty() => {
+            let Some(columns) = ctx.columns else {
+                if ctx.preview {
+                    return Ok(column.to_string());
+                }
+                return Err(format!("{{csv:{}}} used without a payload CSV", column));
+            };
+            let row = columns
+                .get(&account_number(name, ctx)?)
+                .ok_or_else(|| "Payload CSV has no row for this account".to_string())?;
+            let value = row
+                .get(column)
+                .ok_or_else(|| format!("Payload CSV has no column \"{}\"", column))?;
+            if value.is_empty() {
+                return Err(format!("Payload CSV column \"{}\" is empty for this account", column));
+            }
+            Ok(value.clone())
+        }
+        _ => Err(format!("Invalid placeholder {{{}}}", name)),
+    }
+}
+
+fn is_placeholder(name: &str) -> bool {
+    let key = name.split_once(':').map_or(name, |(key, _)| key).trim();
+    PLACEHOLDER_KEYS.contains(&key)
+}
+
+/// Expands `{account}`, `{index}`, `{random}`/`{random:N}` and `{csv:column}`. `{{` and `}}`
+/// stand for literal braces; any other brace is copied as is.
+pub fn render(template: &str, ctx: &TemplateContext) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..pos]);
+        let brace = &rest[pos..pos + 1];
+        let after = &rest[pos + 1..];
+        if let Some(escaped) = after.strip_prefix(brace) {
+            rendered.push_str(brace);
+            rest = escaped;
+            continue;
+        }
+        if brace == "{" {
+            if let Some(close) = after.find('}').filter(|close| is_placeholder(&after[..*close])) {
+                rendered.push_str(&render_placeholder(&after[..close], ctx)?);
+                rest = &after[close + 1..];
+                continue;
+            }
+        }
+        rendered.push_str(brace);
+        rest = after;
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// The link parameters for one profile with every templated field rendered.
+pub fn render_link(link: &TelegramLink, ctx: &TemplateContext) -> Result<TelegramLink, String> {
+    let mut rendered = link.clone();
+    rendered.ref_link = render(&link.ref_link, ctx)?;
+    rendered.message_text = render(&link.message_text, ctx)?;
+    for step in rendered.steps.iter_mut() {
+        step.link = render_link(&step.link, ctx)?;
+    }
+    Ok(rendered)
+}
+
+/// Renders the link as profile 1 would see it, to validate templates before any launch.
+pub fn preview_link(link: &TelegramLink) -> Result<TelegramLink, String> {
+    if !has_placeholders(link) {
+        return Ok(link.clone());
+    }
+    render_link(
+        link,
+        &TemplateContext {
+            profile: 1,
+            index: 1,
+            columns: None,
+            preview: true,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(profile: i32, columns: Option<&AccountColumns>) -> TemplateContext<'_> {
+        TemplateContext {
+            profile,
+            index: 3,
+            columns,
+            preview: false,
+        }
+    }
+
+    fn columns_from(csv: &str) -> AccountColumns {
+        let path = std::env::temp_dir().join(format!("abuseapp-payload-{}.csv", uuid::Uuid::new_v4()));
+        fs::write(&path, csv).unwrap();
+        let columns = load_account_columns(&path);
+        let _ = fs::remove_file(path);
+        columns.unwrap()
+    }
+
+    #[test]
+    fn renders_account_and_index() {
+        assert_eq!(render("ref_{account}_{ index }", &ctx(5, None)).unwrap(), "ref_5_3");
+    }
+
+    #[test]
+    fn random_length_is_bounded() {
+        assert_eq!(render("{random}", &ctx(1, None)).unwrap().len(), DEFAULT_RANDOM_LEN);
+        assert_eq!(render("{random:1}", &ctx(1, None)).unwrap().len(), 1);
+        assert_eq!(render("{random:64}", &ctx(1, None)).unwrap().len(), MAX_RANDOM_LEN);
+        assert!(render("{random:0}", &ctx(1, None)).is_err());
+        assert!(render("{random:65}", &ctx(1, None)).is_err());
+        assert!(render("{random:x}", &ctx(1, None)).is_err());
+    }
+
+    #[test]
+    fn unknown_braces_and_escapes_pass_through() {
+        let rendered = render(r#"{"a":{{account}}} {x} {account}"#, &ctx(5, None)).unwrap();
+        assert_eq!(rendered, r#"{"a":{account}} {x} 5"#);
+        assert_eq!(render("tail {", &ctx(5, None)).unwrap(), "tail {");
+    }
+
+    #[test]
+    fn csv_columns_come_from_the_account_row() {
+        let columns = columns_from("\u{feff}name,account,code\n\"a, \"\"b\"\"\",TG 5,xyz\nq,6,\n");
+        assert_eq!(columns[&5]["name"], "a, \"b\"");
+        assert_eq!(render("{csv:code}-{csv:name}", &ctx(5, Some(&columns))).unwrap(), "xyz-a, \"b\"");
+
+        let missing_row = render("{csv:code}", &ctx(7, Some(&columns))).unwrap_err();
+        assert!(missing_row.contains("no row"), "{}", missing_row);
+        let missing_column = render("{csv:other}", &ctx(5, Some(&columns))).unwrap_err();
+        assert!(missing_column.contains("no column"), "{}", missing_column);
+        let empty = render("{csv:code}", &ctx(6, Some(&columns))).unwrap_err();
+        assert!(empty.contains("is empty"), "{}", empty);
+    }
+
+    #[test]
+    fn stand_in_ids_have_no_account_number() {
+        let columns = columns_from("account,code\n1,xyz\n");
+        assert!(render("{account}", &ctx(-1, None)).is_err());
+        assert!(render("{csv:code}", &ctx(-1, Some(&columns))).is_err());
+        assert_eq!(render("{index}", &ctx(-1, None)).unwrap(), "3");
+    }
+}
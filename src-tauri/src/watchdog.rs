@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::launcher::LaunchReport;
+
+static WATCHED_ACCOUNTS: OnceLock<Mutex<HashMap<String, WatchedAccount>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    pub max_restarts: u32,
+    pub resend_link: bool,
+}
+
+/// A Telegram account launched in this app session that the watchdog keeps alive.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedAccount {
+    pub profile: i32,
+    /// Account folder name under `telegram_folder_path`.
+    pub folder: String,
+    pub telegram_folder_path: String,
+    /// Normalized account folder, matched the same way as `get_pids_for_account_dirs`.
+    pub account_dir: String,
+    pub link: Option<String>,
+    pub start_in_tray: bool,
+    pub restarts: u32,
+    /// Crashes only count once the client has been seen running by a watchdog pass.
+    pub seen_running: bool,
+    pub gave_up: bool,
+    pub last_restart_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum WatchdogAction {
+    Restart { account: WatchedAccount, attempt: u32 },
+    GiveUp { account: WatchedAccount },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartEvent {
+    pub profile: i32,
+    pub attempt: u32,
+    pub max_restarts: u32,
+    pub link_resent: bool,
+    pub succeeded: bool,
+    pub report: Option<LaunchReport>,
+}
+
+fn watched() -> &'static Mutex<HashMap<String, WatchedAccount>> {
+    WATCHED_ACCOUNTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Starts (or restarts) supervising an account with a fresh retry budget.
+pub fn watch(
+    profile: i32,
+    folder: &str,
+    telegram_folder_path: &str,
+    account_dir: String,
+    link: Option<String>,
+    start_in_tray: bool,
+) {
+    let Ok(mut accounts) = watched().lock() else {
+        return;
+    };
+    accounts.insert(
+        account_dir.clone(),
+        WatchedAccount {
+            profile,
+            folder: folder.to_string(),
+            telegram_folder_path: telegram_folder_path.to_string(),
+            account_dir,
+            link,
+            start_in_tray,
+            restarts: 0,
+            seen_running: false,
+            gave_up: false,
+            last_restart_at: None,
+        },
+    );
+}
+
+/// Stops supervising accounts whose folder matches one of `paths` (account folders or exe paths),
+/// so a deliberate close is not mistaken for a crash.
+pub fn forget(paths: &[String]) {
+    let Ok(mut accounts) = watched().lock() else {
+        return;
+    };
+    accounts.retain(|dir, _| !paths.iter().any(|path| crate::path_in_account_dir(path, dir)));
+}
+
+pub fn snapshot() -> Vec<WatchedAccount> {
+    let mut accounts: Vec<WatchedAccount> = watched()
+        .lock()
+        .map(|accounts| accounts.values().cloned().collect())
+        .unwrap_or_default();
+    accounts.sort_by_key(|account| account.profile);
+    accounts
+}
+
+/// A failed relaunch leaves the account marked as seen so the next pass retries it.
+pub fn restart_finished(account_dir: &str, succeeded: bool) {
+    if succeeded {
+        return;
+    }
+    if let Ok(mut accounts) = watched().lock() {
+        if let Some(account) = accounts.get_mut(account_dir) {
+            account.seen_running = true;
+        }
+    }
+}
+
+/// One supervision pass: marks live accounts as seen and returns what to do with the dead ones.
+pub fn check(config: WatchdogConfig) -> Vec<WatchdogAction> {
+    let running_paths: Vec<String> = crate::list_running_telegram_processes()
+        .into_iter()
+        .map(|(_pid, _name, path)| crate::normalize_path_for_match(&path))
+        .collect();
+
+    let Ok(mut accounts) = watched().lock() else {
+        return Vec::new();
+    };
+    let mut actions = Vec::new();
+    for account in accounts.values_mut().filter(|account| !account.gave_up) {
+        let running = running_paths
+            .iter()
+            .any(|path| crate::path_in_account_dir(path, &account.account_dir));
+        if running {
+            account.seen_running = true;
+            continue;
+        }
+        if !account.seen_running {
+            continue;
+        }
+
+        if account.restarts >= config.max_restarts {
+            account.gave_up = true;
+            actions.push(WatchdogAction::GiveUp {
+                account: account.clone(),
+            });
+            continue;
+        }
+        account.restarts += 1;
+        account.seen_running = false;
+        account.last_restart_at = Some(Utc::now());
+        actions.push(WatchdogAction::Restart {
+            account: account.clone(),
+            attempt: account.restarts,
+        });
+    }
+    actions
+}